@@ -11,8 +11,7 @@ use chaos_symphony_ecs::{
     types::{Identity, NetworkIdentity, Role},
 };
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let mut app = App::new();
 
     app.add_plugins(chaos_symphony_ecs::DefaultPlugins {
@@ -21,12 +20,15 @@ async fn main() {
             log_filter: "chaos_symphony_ai".to_string(),
             title: "Chaos Symphony AI".to_string(),
         },
+        network_client_config: default(),
         network_identity: NetworkIdentity {
             inner: Identity {
                 id: Uuid::from_str("d908808f-073d-4c57-9c08-bf91ba2b1bce").unwrap(),
                 noun: "ai".to_string(),
             },
         },
+        network_runtime: default(),
+        network_server_config: default(),
         role: Role::Client,
     });
 
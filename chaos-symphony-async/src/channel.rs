@@ -0,0 +1,145 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        mpsc::{SendError, TryRecvError},
+        Arc, Mutex,
+    },
+    task::Waker,
+};
+
+/// Creates an unbounded channel whose [`Receiver`] wakes the task awaiting it.
+#[must_use]
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Mutex::new(State {
+        is_receiver_dropped: false,
+        queue: VecDeque::new(),
+        senders: 1,
+        waker: None,
+    }));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// State.
+struct State<T> {
+    is_receiver_dropped: bool,
+    queue: VecDeque<T>,
+    senders: usize,
+    waker: Option<Waker>,
+}
+
+/// Sender.
+pub struct Sender<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().expect("poisoned").senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock().expect("poisoned");
+        state.senders -= 1;
+        if state.senders == 0 {
+            if let Some(waker) = state.waker.take() {
+                drop(state);
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Sends a value, waking the task awaiting the [`Receiver`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the [`Receiver`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.lock().expect("poisoned");
+        if state.is_receiver_dropped {
+            return Err(SendError(value));
+        }
+
+        state.queue.push_back(value);
+        if let Some(waker) = state.waker.take() {
+            drop(state);
+            waker.wake();
+        }
+        Ok(())
+    }
+}
+
+/// Receiver.
+pub struct Receiver<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock().expect("poisoned");
+        state.is_receiver_dropped = true;
+        state.queue.clear();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Registers the waker woken by the next value or once disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub fn register(&self, waker: &Waker) {
+        let mut state = self.shared.lock().expect("poisoned");
+        match &state.waker {
+            Some(registered) if registered.will_wake(waker) => {}
+            _ => state.waker = Some(waker.clone()),
+        }
+    }
+
+    /// Try to receive a value.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if empty, or disconnected once every [`Sender`] is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.lock().expect("poisoned");
+        match state.queue.pop_front() {
+            Some(value) => Ok(value),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
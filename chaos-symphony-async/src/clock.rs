@@ -0,0 +1,78 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::Waker,
+    time::Duration,
+};
+
+/// Clock.
+///
+/// Elapsed time driving [`Timeout`](crate::Timeout), advanced by its owner,
+/// such as from Bevy `Time` once per frame.
+#[derive(Debug, Clone, Default)]
+pub struct Clock {
+    inner: Arc<Mutex<ClockState>>,
+}
+
+/// Clock State.
+#[derive(Debug, Default)]
+struct ClockState {
+    elapsed: Duration,
+    timers: Vec<(Duration, Waker)>,
+}
+
+impl Clock {
+    /// Creates a new [`Clock`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances to the elapsed time, waking timeouts past their deadline.
+    ///
+    /// Never goes backwards.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub fn advance_to(&self, elapsed: Duration) {
+        let mut state = self.inner.lock().expect("poisoned");
+        state.elapsed = state.elapsed.max(elapsed);
+
+        let elapsed = state.elapsed;
+        let mut expired = Vec::new();
+        state.timers.retain(|(deadline, waker)| {
+            let is_expired = *deadline <= elapsed;
+            if is_expired {
+                expired.push(waker.clone());
+            }
+            !is_expired
+        });
+        drop(state);
+
+        expired.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns the elapsed time of this [`Clock`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.inner.lock().expect("poisoned").elapsed
+    }
+
+    /// Registers the waker woken once the deadline has passed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn register(&self, deadline: Duration, waker: &Waker) {
+        let mut state = self.inner.lock().expect("poisoned");
+        if !state.timers.iter().any(|(registered, registered_waker)| {
+            *registered == deadline && registered_waker.will_wake(waker)
+        }) {
+            state.timers.push((deadline, waker.clone()));
+        }
+    }
+}
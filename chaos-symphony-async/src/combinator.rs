@@ -0,0 +1,279 @@
+use std::{
+    pin::Pin,
+    task::{Context, Waker},
+    time::Duration,
+};
+
+use crate::{Clock, Poll, PollError};
+
+/// Future Ext.
+///
+/// Combinators for any [`std::future::Future`], pollable from Bevy systems
+/// with [`FutureExt::try_poll`] or awaited from async code.
+pub trait FutureExt
+where
+    Self: std::future::Future + Sized,
+{
+    /// And then.
+    ///
+    /// Chains a future created from the value once ready, unless an error.
+    fn and_then<F, G, T, U, E>(self, f: G) -> AndThen<Self, G, F>
+    where
+        Self: std::future::Future<Output = Result<T, E>>,
+        F: std::future::Future<Output = Result<U, E>>,
+        G: FnOnce(T) -> F,
+    {
+        AndThen {
+            state: AndThenState::First {
+                future: self,
+                f: Some(f),
+            },
+        }
+    }
+
+    /// Map.
+    ///
+    /// Maps the output once ready.
+    fn map<G, U>(self, f: G) -> Map<Self, G>
+    where
+        G: FnOnce(Self::Output) -> U,
+    {
+        Map {
+            f: Some(f),
+            future: self,
+        }
+    }
+
+    /// Timeout.
+    ///
+    /// Ready with [`PollError::TimedOut`] when not ready before the clock has
+    /// advanced by the timeout. The future is dropped with the [`Timeout`].
+    fn timeout(self, clock: &Clock, timeout: Duration) -> Timeout<Self> {
+        Timeout {
+            clock: clock.clone(),
+            deadline: clock.elapsed() + timeout,
+            future: self,
+        }
+    }
+
+    /// Try poll.
+    ///
+    /// Polls once without registering a waker, for use from Bevy systems.
+    fn try_poll(&mut self) -> Poll<Self::Output>
+    where
+        Self: Unpin,
+    {
+        match Pin::new(self).poll(&mut Context::from_waker(Waker::noop())) {
+            std::task::Poll::Ready(output) => Poll::Ready(output),
+            std::task::Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> FutureExt for T where T: std::future::Future {}
+
+/// And Then.
+#[must_use = "futures do nothing unless polled"]
+pub struct AndThen<Fut, G, F> {
+    state: AndThenState<Fut, G, F>,
+}
+
+/// And Then State.
+enum AndThenState<Fut, G, F> {
+    First { future: Fut, f: Option<G> },
+    Second { future: F },
+    Done,
+}
+
+impl<Fut, G, F> Unpin for AndThen<Fut, G, F>
+where
+    Fut: Unpin,
+    F: Unpin,
+{
+}
+
+impl<Fut, G, F, T, U, E> std::future::Future for AndThen<Fut, G, F>
+where
+    Fut: std::future::Future<Output = Result<T, E>> + Unpin,
+    F: std::future::Future<Output = Result<U, E>> + Unpin,
+    G: FnOnce(T) -> F,
+{
+    type Output = Result<U, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                AndThenState::First { future, f } => match Pin::new(future).poll(cx) {
+                    std::task::Poll::Ready(Ok(value)) => {
+                        let f = f.take().expect("polled after completion");
+                        this.state = AndThenState::Second { future: f(value) };
+                    }
+                    std::task::Poll::Ready(Err(error)) => {
+                        this.state = AndThenState::Done;
+                        return std::task::Poll::Ready(Err(error));
+                    }
+                    std::task::Poll::Pending => return std::task::Poll::Pending,
+                },
+                AndThenState::Second { future } => {
+                    let poll = Pin::new(future).poll(cx);
+                    if poll.is_ready() {
+                        this.state = AndThenState::Done;
+                    }
+                    return poll;
+                }
+                AndThenState::Done => panic!("polled after completion"),
+            }
+        }
+    }
+}
+
+/// Map.
+#[must_use = "futures do nothing unless polled"]
+pub struct Map<Fut, G> {
+    f: Option<G>,
+    future: Fut,
+}
+
+impl<Fut, G> Unpin for Map<Fut, G> where Fut: Unpin {}
+
+impl<Fut, G, U> std::future::Future for Map<Fut, G>
+where
+    Fut: std::future::Future + Unpin,
+    G: FnOnce(Fut::Output) -> U,
+{
+    type Output = U;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        Pin::new(&mut this.future).poll(cx).map(|output| {
+            let f = this.f.take().expect("polled after completion");
+            f(output)
+        })
+    }
+}
+
+/// Timeout.
+#[must_use = "futures do nothing unless polled"]
+pub struct Timeout<Fut> {
+    clock: Clock,
+    deadline: Duration,
+    future: Fut,
+}
+
+impl<Fut, T> std::future::Future for Timeout<Fut>
+where
+    Fut: std::future::Future<Output = Result<T, PollError>> + Unpin,
+{
+    type Output = Result<T, PollError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        if let std::task::Poll::Ready(output) = Pin::new(&mut this.future).poll(cx) {
+            return std::task::Poll::Ready(output);
+        }
+
+        // registered before checking, so an advance in between still wakes.
+        this.clock.register(this.deadline, cx.waker());
+        if this.clock.elapsed() >= this.deadline {
+            return std::task::Poll::Ready(Err(PollError::TimedOut));
+        }
+
+        std::task::Poll::Pending
+    }
+}
+
+/// Join All.
+///
+/// Ready with every output, in order, once all futures are ready.
+pub fn join_all<I>(futures: I) -> JoinAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: std::future::Future + Unpin,
+{
+    let futures: Vec<_> = futures.into_iter().collect();
+    JoinAll {
+        outputs: futures.iter().map(|_| None).collect(),
+        futures,
+    }
+}
+
+/// Join All.
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinAll<Fut>
+where
+    Fut: std::future::Future,
+{
+    futures: Vec<Fut>,
+    outputs: Vec<Option<Fut::Output>>,
+}
+
+impl<Fut> Unpin for JoinAll<Fut> where Fut: std::future::Future + Unpin {}
+
+impl<Fut> std::future::Future for JoinAll<Fut>
+where
+    Fut: std::future::Future + Unpin,
+{
+    type Output = Vec<Fut::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        let this = self.get_mut();
+        this.futures
+            .iter_mut()
+            .zip(this.outputs.iter_mut())
+            .filter(|(_, output)| output.is_none())
+            .for_each(|(future, output)| {
+                if let std::task::Poll::Ready(ready) = Pin::new(future).poll(cx) {
+                    *output = Some(ready);
+                }
+            });
+
+        if this.outputs.iter().any(Option::is_none) {
+            return std::task::Poll::Pending;
+        }
+
+        std::task::Poll::Ready(this.outputs.iter_mut().filter_map(Option::take).collect())
+    }
+}
+
+/// Select.
+///
+/// Ready with the index and output of the first future ready.
+///
+/// # Panics
+///
+/// Will panic if there are no futures.
+pub fn select<I>(futures: I) -> Select<I::Item>
+where
+    I: IntoIterator,
+    I::Item: std::future::Future + Unpin,
+{
+    let futures: Vec<_> = futures.into_iter().collect();
+    assert!(!futures.is_empty(), "select requires at least one future");
+    Select { futures }
+}
+
+/// Select.
+#[must_use = "futures do nothing unless polled"]
+pub struct Select<Fut> {
+    futures: Vec<Fut>,
+}
+
+impl<Fut> std::future::Future for Select<Fut>
+where
+    Fut: std::future::Future + Unpin,
+{
+    type Output = (usize, Fut::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        self.get_mut()
+            .futures
+            .iter_mut()
+            .enumerate()
+            .find_map(|(index, future)| match Pin::new(future).poll(cx) {
+                std::task::Poll::Ready(output) => Some((index, output)),
+                std::task::Poll::Pending => None,
+            })
+            .map_or(std::task::Poll::Pending, std::task::Poll::Ready)
+    }
+}
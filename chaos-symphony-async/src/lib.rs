@@ -3,40 +3,255 @@
 
 //! Chaos Symphony Async
 
-use std::sync::{mpsc::TryRecvError, Mutex};
+mod channel;
+mod clock;
+mod combinator;
+
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{mpsc::TryRecvError, Mutex},
+    task::Context,
+    time::{Duration, Instant},
+};
+
+pub use channel::{channel, Receiver, Sender};
+pub use clock::Clock;
+pub use combinator::{join_all, select, AndThen, FutureExt, JoinAll, Map, Select, Timeout};
+
+/// Cancel.
+type Cancel = Box<dyn FnOnce() + Send>;
 
 /// Future.
-#[derive(Debug)]
 pub struct Future<T> {
-    receiver: Mutex<std::sync::mpsc::Receiver<T>>,
+    cancel: Mutex<Option<Cancel>>,
+    deadline: Option<Instant>,
+    receiver: Receiver<T>,
+}
+
+impl<T> fmt::Debug for Future<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Future")
+            .field("deadline", &self.deadline)
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Future<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
 }
 
 impl<T> Future<T> {
     /// Creates a new [`Future`].
     #[must_use]
-    pub fn new(receiver: std::sync::mpsc::Receiver<T>) -> Self {
+    pub fn new(receiver: Receiver<T>) -> Self {
         Self {
-            receiver: Mutex::new(receiver),
+            cancel: Mutex::new(None),
+            deadline: None,
+            receiver,
         }
     }
 
+    /// Calls the cancel function, unless already called or ready.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    fn cancel(&self) {
+        if let Some(cancel) = self.cancel.lock().expect("poisoned").take() {
+            cancel();
+        }
+    }
+
+    /// Returns the deadline of this [`Future`].
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// With cancel.
+    ///
+    /// Calls the function when dropped or timed out before ready.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[must_use]
+    pub fn with_cancel(self, cancel: impl FnOnce() + Send + 'static) -> Self {
+        *self.cancel.lock().expect("poisoned") = Some(Box::new(cancel));
+        self
+    }
+
+    /// With timeout.
+    ///
+    /// Times out when not ready within the timeout from now.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
     /// Try Poll.
     ///
     /// Will disconnect bevy-tokio bridge on first [`Poll::Ready`].
     ///
     /// # Errors
     ///
-    /// Will return `Err` if bevy-tokio bridge is disconnected or empty.
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the deadline
+    /// has passed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub fn try_poll(&self) -> Poll<Result<T, PollError>> {
+        let is_timed_out = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+
+        let poll = match self.receiver.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(_) if is_timed_out => Poll::Ready(Err(PollError::TimedOut)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(Err(PollError::Disconnected)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        };
+
+        match poll {
+            Poll::Ready(Err(PollError::TimedOut)) => self.cancel(),
+            Poll::Ready(_) => {
+                self.cancel.lock().expect("poisoned").take();
+            }
+            Poll::Pending => {}
+        }
+
+        poll
+    }
+}
+
+impl<T> std::future::Future for Future<T> {
+    type Output = Result<T, PollError>;
+
+    /// Polls as [`Future::try_poll`], woken once ready.
+    ///
+    /// A deadline set by [`Future::with_timeout`] does not wake the task, it is
+    /// checked when the bevy-tokio bridge discards the request.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
+        // registered before polling, so a value sent in between still wakes.
+        self.receiver.register(cx.waker());
+        Future::try_poll(&self).into()
+    }
+}
+
+/// Stream.
+///
+/// Like [`Future`], but ready with each value received until the sender is
+/// dropped.
+pub struct Stream<T> {
+    cancel: Mutex<Option<Cancel>>,
+    deadline: Option<Instant>,
+    receiver: Receiver<T>,
+}
+
+impl<T> fmt::Debug for Stream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream")
+            .field("deadline", &self.deadline)
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl<T> Stream<T> {
+    /// Creates a new [`Stream`].
+    #[must_use]
+    pub fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            cancel: Mutex::new(None),
+            deadline: None,
+            receiver,
+        }
+    }
+
+    /// Calls the cancel function, unless already called or disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    fn cancel(&self) {
+        if let Some(cancel) = self.cancel.lock().expect("poisoned").take() {
+            cancel();
+        }
+    }
+
+    /// Returns the deadline of this [`Stream`].
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// With cancel.
+    ///
+    /// Calls the function when dropped or timed out before disconnected.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[must_use]
+    pub fn with_cancel(self, cancel: impl FnOnce() + Send + 'static) -> Self {
+        *self.cancel.lock().expect("poisoned") = Some(Box::new(cancel));
+        self
+    }
+
+    /// With timeout.
+    ///
+    /// Times out when not disconnected within the timeout from now.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Try Poll.
+    ///
+    /// Values received before the deadline are ready even once it has passed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the deadline
+    /// has passed.
     ///
     /// # Panics
     ///
     /// Will panic if [`Mutex`] is poisoned.
     pub fn try_poll(&self) -> Poll<Result<T, PollError>> {
-        match self.receiver.lock().expect("poisoned").try_recv() {
+        let is_timed_out = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+
+        let poll = match self.receiver.try_recv() {
             Ok(value) => Poll::Ready(Ok(value)),
             Err(TryRecvError::Disconnected) => Poll::Ready(Err(PollError::Disconnected)),
+            Err(TryRecvError::Empty) if is_timed_out => Poll::Ready(Err(PollError::TimedOut)),
             Err(TryRecvError::Empty) => Poll::Pending,
+        };
+
+        match poll {
+            Poll::Ready(Err(PollError::TimedOut)) => self.cancel(),
+            Poll::Ready(Err(PollError::Disconnected)) => {
+                self.cancel.lock().expect("poisoned").take();
+            }
+            _ => {}
         }
+
+        poll
     }
 }
 
@@ -64,9 +279,179 @@ impl<T> Poll<T> {
     }
 }
 
+impl<T> From<Poll<T>> for std::task::Poll<T> {
+    fn from(value: Poll<T>) -> Self {
+        match value {
+            Poll::Ready(t) => Self::Ready(t),
+            Poll::Pending => Self::Pending,
+        }
+    }
+}
+
 /// Poll Error.
 #[derive(Debug)]
 pub enum PollError {
+    /// Decode.
+    ///
+    /// Ready, but unable to decode the value received.
+    Decode(Box<dyn std::error::Error + Send + Sync>),
+
     /// Disconnected.
     Disconnected,
+
+    /// Timed Out.
+    TimedOut,
+}
+
+impl fmt::Display for PollError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(_) => write!(f, "unable to decode"),
+            Self::Disconnected => write!(f, "bevy-tokio bridge disconnected"),
+            Self::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+impl std::error::Error for PollError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(error) => Some(error.as_ref()),
+            Self::Disconnected | Self::TimedOut => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future as _,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Wake, Waker},
+        time::Duration,
+    };
+
+    use crate::{join_all, Clock, Future, FutureExt as _, Poll, PollError, Stream};
+
+    /// Counts the number of times it is woken.
+    #[derive(Default)]
+    struct CountingWaker {
+        count: AtomicUsize,
+    }
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_cancel_on_drop() {
+        // Arrange
+        let cancelled = Arc::new(AtomicUsize::new(0));
+
+        let (_pending_sender, receiver) = crate::channel::<()>();
+        let counter = cancelled.clone();
+        let pending = Future::new(receiver).with_cancel(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let (ready_sender, receiver) = crate::channel();
+        let counter = cancelled.clone();
+        let ready = Future::new(receiver).with_cancel(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        ready_sender.send(()).unwrap();
+
+        // Act
+        let pending_poll = pending.try_poll();
+        let ready_poll = ready.try_poll();
+        drop(pending);
+        drop(ready);
+
+        // Assert
+        assert!(matches!(pending_poll, Poll::Pending));
+        assert!(matches!(ready_poll, Poll::Ready(Ok(()))));
+        assert_eq!(cancelled.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_stream_until_disconnected() {
+        // Arrange
+        let cancelled = Arc::new(AtomicUsize::new(0));
+
+        let (sender, receiver) = crate::channel();
+        let counter = cancelled.clone();
+        let stream = Stream::new(receiver).with_cancel(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        drop(sender);
+
+        // Act
+        let first = stream.try_poll();
+        let second = stream.try_poll();
+        let last = stream.try_poll();
+        drop(stream);
+
+        // Assert
+        assert!(matches!(first, Poll::Ready(Ok(1))));
+        assert!(matches!(second, Poll::Ready(Ok(2))));
+        assert!(matches!(last, Poll::Ready(Err(PollError::Disconnected))));
+        assert_eq!(cancelled.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_wake_on_send() {
+        // Arrange
+        let counting_waker = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counting_waker.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let (sender, receiver) = crate::channel();
+        let mut future = Future::new(receiver);
+
+        // Act
+        let pending_poll = Pin::new(&mut future).poll(&mut cx);
+        sender.send(1).unwrap();
+        let ready_poll = Pin::new(&mut future).poll(&mut cx);
+
+        // Assert
+        assert!(pending_poll.is_pending());
+        assert!(matches!(ready_poll, std::task::Poll::Ready(Ok(1))));
+        assert_eq!(counting_waker.count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_combinators() {
+        // Arrange
+        let clock = Clock::new();
+
+        let (sender, receiver) = crate::channel();
+        let mut joined = join_all([Future::new(receiver), Future::new(crate::channel().1)])
+            .map(|outputs| outputs.into_iter().flatten().count());
+        sender.send(1).unwrap();
+
+        let (_pending_sender, receiver) = crate::channel::<()>();
+        let mut timeout = Future::new(receiver).timeout(&clock, Duration::from_secs(1));
+
+        // Act
+        let joined_poll = joined.try_poll();
+        let pending_poll = timeout.try_poll();
+        clock.advance_to(Duration::from_secs(1));
+        let timed_out_poll = timeout.try_poll();
+
+        // Assert
+        assert!(matches!(joined_poll, Poll::Ready(1)));
+        assert!(matches!(pending_poll, Poll::Pending));
+        assert!(matches!(
+            timed_out_poll,
+            Poll::Ready(Err(PollError::TimedOut))
+        ));
+    }
 }
@@ -0,0 +1,96 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::Uuid};
+use chaos_symphony_async::{Poll, PollError};
+use chaos_symphony_protocol::{Decode, MessageCallback};
+
+/// Callback Plugin.
+///
+/// Polls every [`MessageCallback<T>`], removing it once ready and sending
+/// [`ResponseReceived<T>`] or [`RequestFailed<T>`] before [`Update`].
+#[allow(clippy::module_name_repetitions)]
+pub struct CallbackPlugin<T> {
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> CallbackPlugin<T> {
+    /// Creates a new [`CallbackPlugin`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self { _t: PhantomData }
+    }
+}
+
+impl<T> Default for CallbackPlugin<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Plugin for CallbackPlugin<T>
+where
+    T: Decode + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.add_event::<ResponseReceived<T>>()
+            .add_event::<RequestFailed<T>>()
+            .add_systems(PreUpdate, poll::<T>);
+    }
+}
+
+/// Response Received.
+#[derive(Debug, Clone, Event)]
+pub struct ResponseReceived<T> {
+    /// Entity.
+    ///
+    /// Owner of the [`MessageCallback`].
+    pub entity: Entity,
+
+    /// Inner.
+    pub inner: T,
+}
+
+/// Request Failed.
+#[derive(Debug, Event)]
+pub struct RequestFailed<T> {
+    /// Entity.
+    ///
+    /// Owner of the [`MessageCallback`].
+    pub entity: Entity,
+
+    /// Error.
+    pub error: PollError,
+
+    /// Id.
+    pub id: Uuid,
+
+    _t: PhantomData<fn() -> T>,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn poll<T>(
+    mut commands: Commands,
+    mut received: EventWriter<ResponseReceived<T>>,
+    mut failed: EventWriter<RequestFailed<T>>,
+    callbacks: Query<(Entity, &MessageCallback<T>)>,
+) where
+    T: Decode + Send + Sync + 'static,
+{
+    callbacks.for_each(|(entity, callback)| {
+        let Poll::Ready(result) = callback.try_poll() else {
+            return;
+        };
+
+        commands.entity(entity).remove::<MessageCallback<T>>();
+
+        match result {
+            Ok(inner) => received.send(ResponseReceived { entity, inner }),
+            Err(error) => failed.send(RequestFailed {
+                entity,
+                error,
+                id: callback.id(),
+                _t: PhantomData,
+            }),
+        }
+    });
+}
@@ -1,13 +1,25 @@
-use bevy::{prelude::*, utils::Uuid};
+use bevy::{
+    ecs::system::SystemChangeTick,
+    prelude::*,
+    utils::{HashSet, Uuid},
+};
 use chaos_symphony_async::Poll;
 use chaos_symphony_network_bevy::NetworkEndpoint;
 use chaos_symphony_protocol::{
-    EntityIdentitiesCallback, EntityIdentitiesRequest, EntityIdentitiesRequestPayload,
-    EntityIdentitiesResponse, EntityIdentitiesResponsePayload, EntityIdentityEvent,
-    EntityIdentityEventPayload, Event as _, Request as _, Response as _,
+    CancelEvent, EntityIdentitiesRequest, EntityIdentitiesRequestPayload, EntityIdentitiesResponse,
+    EntityIdentitiesResponsePayload, EntityIdentitiesStream, Response as _, StreamRequest as _,
+};
+
+use crate::{
+    network_router::NetworkAppExt as _,
+    types::{
+        EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority, Identity,
+        NetworkIdentity, ReplicateSource, Role, Untrusted,
+    },
 };
 
-use crate::types::{EntityIdentity, NetworkIdentity, Role, Trusted, Untrusted};
+/// Maximum number of entity identities sent per response.
+const CHUNK_SIZE: usize = 64;
 
 /// Entity Identities Plugin.
 #[allow(clippy::module_name_repetitions)]
@@ -25,15 +37,15 @@ impl EntityIdentitiesPlugin {
 
 impl Plugin for EntityIdentitiesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<Trusted<EntityIdentitiesRequest>>()
-            .add_event::<Untrusted<EntityIdentitiesRequest>>();
+        app.add_network_stream_request::<EntityIdentitiesRequest, EntityIdentitiesResponse>();
 
         match self.role {
             Role::Client | Role::Simulation => {
                 app.add_systems(Update, (callback, initiate));
             }
             Role::Replication => {
-                app.add_systems(Update, request);
+                app.init_resource::<OutgoingStreams>()
+                    .add_systems(Update, (request, cancel, stream).chain());
             }
         }
     }
@@ -42,35 +54,146 @@ impl Plugin for EntityIdentitiesPlugin {
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 struct EntityIdentities;
 
+/// Entity identities received so far on the [`EntityIdentitiesStream`].
+#[derive(Debug, Default, Component)]
+struct Received {
+    inner: HashSet<Identity>,
+}
+
+/// Callback.
+///
+/// Spawns each streamed entity not yet known. Once the stream has ended,
+/// despawns entities under the authority of the server that it no longer knows
+/// of, such as those removed while disconnected. Entities spawned or changed
+/// after the request was sent are kept.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
 #[tracing::instrument(skip_all)]
-fn callback(mut commands: Commands, callbacks: Query<(Entity, &EntityIdentitiesCallback)>) {
-    callbacks.for_each(|(entity, callback)| {
-        let span = error_span!("callback", message_id =% callback.id);
-        let _guard = span.enter();
+fn callback(
+    mut commands: Commands,
+    change_tick: SystemChangeTick,
+    mut streams: Query<(
+        Entity,
+        Ref<EntityIdentitiesStream>,
+        &NetworkIdentity,
+        &mut Received,
+    )>,
+    entity_identities: Query<
+        (
+            Entity,
+            Ref<EntityIdentity>,
+            Option<&EntityReplicationAuthority>,
+            Option<&EntitySimulationAuthority>,
+        ),
+        Without<ReplicateSource>,
+    >,
+    existing: Query<&EntityIdentity>,
+) {
+    if streams.is_empty() {
+        return;
+    }
 
-        if let Poll::Ready(result) = callback.try_poll() {
-            let mut commands = commands.entity(entity);
+    let existing: HashSet<_> = existing
+        .iter()
+        .map(|entity_identity| &entity_identity.inner)
+        .collect();
 
-            commands.remove::<EntityIdentitiesCallback>();
+    streams.for_each_mut(|(entity, stream, network_identity, mut received)| {
+        let span = error_span!("callback", message_id =% stream.id);
+        let _guard = span.enter();
 
-            let Ok(response) = result else {
-                error!("failed to receive response from server");
-                return;
-            };
+        while let Poll::Ready(result) = stream.try_poll() {
+            let response = match result {
+                Ok(Some(response)) => response,
+                Ok(None) => {
+                    info!("accepted by server");
+                    commands
+                        .entity(entity)
+                        .remove::<(EntityIdentitiesStream, Received)>()
+                        .insert(EntityIdentities);
 
-            match response.payload {
-                EntityIdentitiesResponsePayload::Failure => {
-                    error!("rejected by server");
+                    entity_identities
+                        .iter()
+                        .filter(|(_, _, replication_authority, simulation_authority)| {
+                            let authority = replication_authority
+                                .map(|authority| &authority.identity)
+                                .or(simulation_authority.map(|authority| &authority.identity));
+                            authority == Some(&network_identity.inner)
+                        })
+                        .filter(|(_, entity_identity, _, _)| {
+                            !entity_identity
+                                .last_changed()
+                                .is_newer_than(stream.last_changed(), change_tick.this_run())
+                        })
+                        .filter(|(_, entity_identity, _, _)| {
+                            !received.inner.contains(&entity_identity.inner)
+                        })
+                        .for_each(|(stale, entity_identity, _, _)| {
+                            info!(entity_identity =? entity_identity.inner, "despawning stale entity");
+                            commands.entity(stale).despawn();
+                        });
+                    return;
                 }
-                EntityIdentitiesResponsePayload::Success => {
-                    info!("accepted by server");
-                    commands.insert(EntityIdentities);
+                Err(_) => {
+                    error!("failed to receive response from server");
+                    commands
+                        .entity(entity)
+                        .remove::<(EntityIdentitiesStream, Received)>();
+                    return;
                 }
             };
+
+            let EntityIdentitiesResponsePayload::Success { entity_identities } = response.payload
+            else {
+                error!("rejected by server");
+                commands
+                    .entity(entity)
+                    .remove::<(EntityIdentitiesStream, Received)>();
+                return;
+            };
+
+            entity_identities
+                .into_iter()
+                .map(Identity::from)
+                .for_each(|identity| {
+                    if !existing.contains(&identity) && !received.inner.contains(&identity) {
+                        spawn(&mut commands, identity.clone(), network_identity);
+                    }
+                    received.inner.insert(identity);
+                });
         }
     });
 }
 
+/// Spawns an entity streamed from the server.
+///
+/// `ReplicateEntityComponentsPlugin` will need to replicate components, which
+/// requires knowledge of where the components originated from, so entities
+/// streamed from an unrecognized server are skipped.
+fn spawn(commands: &mut Commands, identity: Identity, network_identity: &NetworkIdentity) {
+    let entity_identity = EntityIdentity { inner: identity };
+    match network_identity.inner.noun.as_str() {
+        "replication" => {
+            commands.spawn((
+                entity_identity,
+                EntityReplicationAuthority {
+                    identity: network_identity.inner.clone(),
+                },
+            ));
+        }
+        "simulation" => {
+            commands.spawn((
+                entity_identity,
+                EntitySimulationAuthority {
+                    identity: network_identity.inner.clone(),
+                },
+            ));
+        }
+        noun => {
+            warn!(noun, entity_identity =? entity_identity.inner, "unrecognized server, skipping entity");
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 #[tracing::instrument(skip_all)]
 fn initiate(
@@ -80,7 +203,7 @@ fn initiate(
         (
             With<NetworkIdentity>,
             Without<EntityIdentities>,
-            Without<EntityIdentitiesCallback>,
+            Without<EntityIdentitiesStream>,
         ),
     >,
 ) {
@@ -88,63 +211,312 @@ fn initiate(
         let request =
             EntityIdentitiesRequest::message(Uuid::new_v4(), EntityIdentitiesRequestPayload {});
 
-        let Ok(callback) = request.try_send(endpoint) else {
+        let Ok(stream) = request.try_send(endpoint) else {
             error!("failed to send request");
             return;
         };
 
         info!("request sent");
-        commands.entity(entity).insert(callback);
+        commands
+            .entity(entity)
+            .insert((stream, Received::default()));
     });
 }
 
+/// Outgoing Streams.
+///
+/// Entity identities yet to be streamed in response to each request.
+#[derive(Debug, Default, Resource)]
+struct OutgoingStreams {
+    inner: Vec<OutgoingStream>,
+}
+
+/// Outgoing Stream.
+#[derive(Debug)]
+struct OutgoingStream {
+    endpoint_id: usize,
+    remaining: Vec<Identity>,
+    request_id: Uuid,
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn request(
     mut reader: EventReader<Untrusted<EntityIdentitiesRequest>>,
-    endpoints: Query<&NetworkEndpoint>,
+    mut outgoing_streams: ResMut<OutgoingStreams>,
     entity_identities: Query<&EntityIdentity>,
 ) {
     reader.read().for_each(|request| {
         let span = error_span!("request", message_id =% request.inner.id);
         let _guard = span.enter();
 
-        let Some(source_endpoint_id) = &request.inner.header.source_endpoint_id else {
+        let Some(source_endpoint_id) = request.inner.header.source_endpoint_id else {
             error!("request does not have source endpoint id");
             return;
         };
 
+        // TODO: filter entity identities using requesters permissions.
+        outgoing_streams.inner.push(OutgoingStream {
+            endpoint_id: source_endpoint_id,
+            remaining: entity_identities
+                .iter()
+                .map(|entity_identity| entity_identity.inner.clone())
+                .collect(),
+            request_id: request.inner.id,
+        });
+
+        info!("stream started");
+    });
+}
+
+/// Cancel.
+///
+/// Stops streaming to requesters no longer interested in the response.
+#[allow(clippy::needless_pass_by_value)]
+fn cancel(
+    mut reader: EventReader<Untrusted<CancelEvent>>,
+    mut outgoing_streams: ResMut<OutgoingStreams>,
+) {
+    reader.read().for_each(|cancel| {
+        outgoing_streams.inner.retain(|outgoing_stream| {
+            let is_cancelled = outgoing_stream.request_id == cancel.inner.payload.request_id
+                && Some(outgoing_stream.endpoint_id) == cancel.inner.header.source_endpoint_id;
+            if is_cancelled {
+                info!(message_id =% outgoing_stream.request_id, "stream cancelled");
+            }
+            !is_cancelled
+        });
+    });
+}
+
+/// Stream.
+///
+/// Sends a chunk of each outgoing stream per frame, then ends it.
+#[allow(clippy::needless_pass_by_value)]
+fn stream(mut outgoing_streams: ResMut<OutgoingStreams>, endpoints: Query<&NetworkEndpoint>) {
+    outgoing_streams.inner.retain_mut(|outgoing_stream| {
+        let span = error_span!("stream", message_id =% outgoing_stream.request_id);
+        let _guard = span.enter();
+
         let Some(endpoint) = endpoints
             .iter()
-            .find(|endpoint| endpoint.id() == *source_endpoint_id)
+            .find(|endpoint| endpoint.id() == outgoing_stream.endpoint_id)
         else {
             warn!("endpoint not found");
-            return;
+            return false;
         };
 
+        if outgoing_stream.remaining.is_empty() {
+            if EntityIdentitiesRequest::end(outgoing_stream.request_id)
+                .try_send(endpoint)
+                .is_err()
+            {
+                warn!("failed to send end");
+            }
+
+            info!("stream ended");
+            return false;
+        }
+
+        let count = outgoing_stream.remaining.len().min(CHUNK_SIZE);
         let response = EntityIdentitiesResponse::message(
-            request.inner.id,
-            EntityIdentitiesResponsePayload::Success,
+            outgoing_stream.request_id,
+            EntityIdentitiesResponsePayload::Success {
+                entity_identities: outgoing_stream
+                    .remaining
+                    .drain(..count)
+                    .map(Into::into)
+                    .collect(),
+            },
         );
 
         if response.try_send(endpoint).is_err() {
             warn!("failed to send response");
+            return false;
         }
 
-        info!("sent response");
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, time::TimePlugin, utils::Uuid};
+    use chaos_symphony_network::MemoryNetwork;
 
-        entity_identities.for_each(|entity_identity| {
-            // TODO: filter entity identities using requesters permissions.
+    use chaos_symphony_protocol::EntityIdentitiesStream;
 
-            let request = EntityIdentityEvent::message(
-                Uuid::new_v4(),
-                EntityIdentityEventPayload {
-                    inner: entity_identity.inner.clone().into(),
-                },
-            );
+    use crate::{
+        entity_identities::{
+            EntityIdentities, EntityIdentitiesPlugin, OutgoingStreams, CHUNK_SIZE,
+        },
+        network_router::NetworkRouter,
+        testing,
+        types::{EntityIdentity, EntityReplicationAuthority, Identity, NetworkIdentity, Role},
+    };
 
-            if request.try_send(endpoint).is_err() {
-                warn!("failed to send event");
-            }
+    /// Creates an [`Identity`] with the noun.
+    fn identity(noun: &str) -> Identity {
+        Identity {
+            id: Uuid::new_v4(),
+            noun: noun.to_string(),
+        }
+    }
+
+    /// Spawns an [`EntityIdentity`] under the authority of the identity.
+    fn spawn(app: &mut App, authority: &Identity) -> Identity {
+        let identity = identity("ship");
+        app.world.spawn((
+            EntityIdentity {
+                inner: identity.clone(),
+            },
+            EntityReplicationAuthority {
+                identity: authority.clone(),
+            },
+        ));
+        identity
+    }
+
+    /// Returns the entity identities of the app.
+    fn entity_identities(app: &mut App) -> Vec<Identity> {
+        app.world
+            .query::<&EntityIdentity>()
+            .iter(&app.world)
+            .map(|entity_identity| entity_identity.inner.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_cancelled_on_drop() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let server_identity = identity("replication");
+
+        let mut client = App::new();
+        client.add_plugins((
+            TimePlugin,
+            testing::client(&network),
+            EntityIdentitiesPlugin::new(Role::Client),
+        ));
+
+        let mut server = App::new();
+        server.add_plugins((
+            TimePlugin,
+            testing::server(&network),
+            NetworkRouter,
+            EntityIdentitiesPlugin::new(Role::Replication),
+        ));
+        (0..CHUNK_SIZE * 100).for_each(|_| {
+            spawn(&mut server, &server_identity);
         });
-    });
+
+        let (client_entity, _) = testing::connect(&mut client, &mut server);
+        client
+            .world
+            .entity_mut(client_entity)
+            .insert(NetworkIdentity {
+                inner: server_identity,
+            });
+        testing::until(|| {
+            client.update();
+            server.update();
+            (!server.world.resource::<OutgoingStreams>().inner.is_empty()).then_some(())
+        });
+
+        // Act
+        let request_id = client
+            .world
+            .get::<EntityIdentitiesStream>(client_entity)
+            .unwrap()
+            .id();
+        client
+            .world
+            .entity_mut(client_entity)
+            .remove::<(EntityIdentitiesStream, NetworkIdentity)>();
+        let remaining = |server: &App| {
+            server
+                .world
+                .resource::<OutgoingStreams>()
+                .inner
+                .iter()
+                .find(|outgoing_stream| outgoing_stream.request_id == request_id)
+                .map(|outgoing_stream| outgoing_stream.remaining.len())
+        };
+        let remaining = testing::until(|| {
+            client.update();
+            let before = remaining(&server);
+            server.update();
+            remaining(&server).is_none().then_some(before)
+        });
+
+        // Assert
+        assert!(
+            remaining.is_some_and(|remaining| remaining > CHUNK_SIZE),
+            "stream cancelled before it ended"
+        );
+    }
+
+    #[test]
+    fn test_stream_despawns_stale_entities_of_server() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let server_identity = identity("replication");
+        let shard_identity = identity("replication");
+
+        let mut client = App::new();
+        client.add_plugins((
+            TimePlugin,
+            testing::client(&network),
+            EntityIdentitiesPlugin::new(Role::Client),
+        ));
+
+        let mut server = App::new();
+        server.add_plugins((
+            TimePlugin,
+            testing::server(&network),
+            NetworkRouter,
+            EntityIdentitiesPlugin::new(Role::Replication),
+        ));
+
+        let known = spawn(&mut client, &server_identity);
+        let stale = spawn(&mut client, &server_identity);
+        let shard = spawn(&mut client, &shard_identity);
+        server.world.spawn(EntityIdentity {
+            inner: known.clone(),
+        });
+        let spawned = spawn(&mut server, &server_identity);
+
+        let (client_entity, _) = testing::connect(&mut client, &mut server);
+
+        // Act
+        client
+            .world
+            .entity_mut(client_entity)
+            .insert(NetworkIdentity {
+                inner: server_identity.clone(),
+            });
+        testing::until(|| {
+            client.update();
+            server.update();
+            client
+                .world
+                .get::<EntityIdentities>(client_entity)
+                .map(|_| ())
+        });
+        client.update();
+
+        // Assert
+        let mut identities = entity_identities(&mut client);
+        identities.sort_by_key(|identity| identity.id);
+        let mut expected = vec![known, shard.clone(), spawned];
+        expected.sort_by_key(|identity| identity.id);
+        assert_eq!(identities, expected);
+        assert!(!entity_identities(&mut client).contains(&stale));
+        assert!(client
+            .world
+            .query::<(&EntityIdentity, &EntityReplicationAuthority)>()
+            .iter(&client.world)
+            .all(|(entity_identity, authority)| {
+                entity_identity.inner.id == shard.id || authority.identity == server_identity
+            }));
+    }
 }
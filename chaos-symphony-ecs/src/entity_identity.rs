@@ -1,10 +1,13 @@
-use bevy::{prelude::*, utils::Uuid};
+use bevy::{ecs::system::SystemChangeTick, prelude::*, utils::Uuid};
 use chaos_symphony_network_bevy::NetworkEndpoint;
 use chaos_symphony_protocol::{EntityIdentityEvent, EntityIdentityEventPayload, Event};
 
-use crate::types::{
-    EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority, NetworkIdentity,
-    ReplicateSource, Role, Trusted,
+use crate::{
+    network_router::NetworkAppExt as _,
+    types::{
+        EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority, NetworkIdentity,
+        NetworkSession, ReplicateSource, Role, Trusted,
+    },
 };
 
 /// Entity Identity Plugin.
@@ -23,7 +26,7 @@ impl EntityIdentityPlugin {
 
 impl Plugin for EntityIdentityPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<Trusted<EntityIdentityEvent>>();
+        app.add_network_event::<EntityIdentityEvent>();
 
         app.add_systems(Update, apply_trusted_event);
 
@@ -33,7 +36,7 @@ impl Plugin for EntityIdentityPlugin {
                 app.add_systems(Update, send_trusted_event);
             }
             Role::Simulation => {
-                app.add_systems(Update, broadcast_on_change);
+                app.add_systems(Update, (broadcast_on_change, resync));
             }
         }
     }
@@ -100,7 +103,7 @@ fn send_trusted_event(
                 let message = event.inner.clone();
                 if message.try_send(endpoint).is_err() {
                     error!("failed to send event");
-                };
+                }
             });
     });
 }
@@ -125,3 +128,137 @@ fn broadcast_on_change(
         });
     });
 }
+
+/// Resync.
+///
+/// Sends owned [`EntityIdentity`] to a newly authenticated [`NetworkEndpoint`].
+/// A resumed [`NetworkSession`] only receives those changed since it was last
+/// seen, as the server still knows of the rest.
+#[allow(clippy::needless_pass_by_value)]
+fn resync(
+    change_tick: SystemChangeTick,
+    entity_identities: Query<Ref<EntityIdentity>, With<ReplicateSource>>,
+    endpoints: Query<(&NetworkEndpoint, &NetworkSession), Added<NetworkSession>>,
+) {
+    endpoints.for_each(|(endpoint, session)| {
+        entity_identities
+            .iter()
+            .filter(|entity_identity| {
+                session.last_seen.is_none_or(|last_seen| {
+                    entity_identity
+                        .last_changed()
+                        .is_newer_than(last_seen, change_tick.this_run())
+                })
+            })
+            .for_each(|entity_identity| {
+                let message = EntityIdentityEvent::message(
+                    Uuid::new_v4(),
+                    EntityIdentityEventPayload {
+                        inner: entity_identity.inner.clone().into(),
+                    },
+                );
+
+                if message.try_send(endpoint).is_err() {
+                    error!("failed to send message");
+                }
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::component::Tick, prelude::*, utils::Uuid};
+    use chaos_symphony_network::MemoryNetwork;
+    use chaos_symphony_network_bevy::{NetworkEndpoint, NetworkRecv};
+    use chaos_symphony_protocol::{Decode as _, EntityIdentityEvent};
+
+    use crate::{
+        entity_identity::resync,
+        testing,
+        types::{EntityIdentity, Identity, NetworkSession, ReplicateSource},
+    };
+
+    /// Spawns an owned [`EntityIdentity`].
+    fn spawn(app: &mut App) -> Identity {
+        let identity = Identity {
+            id: Uuid::new_v4(),
+            noun: "ship".to_string(),
+        };
+        app.world.spawn((
+            EntityIdentity {
+                inner: identity.clone(),
+            },
+            ReplicateSource,
+        ));
+        identity
+    }
+
+    /// Resyncs a session last seen between spawning two entities, returning
+    /// both and the identities received by the server.
+    fn resync_session(is_resumed: bool) -> (Identity, Identity, Vec<Identity>) {
+        let network = MemoryNetwork::new();
+
+        let mut client = App::new();
+        client
+            .add_plugins(testing::client(&network))
+            .add_systems(Update, resync);
+
+        let mut server = App::new();
+        server.add_plugins(testing::server(&network));
+
+        let (client_entity, server_entity) = testing::connect(&mut client, &mut server);
+
+        let before = spawn(&mut client);
+        let last_seen: Tick = client.world.change_tick();
+        client.world.increment_change_tick();
+        let after = spawn(&mut client);
+
+        client
+            .world
+            .entity_mut(client_entity)
+            .insert(NetworkSession {
+                last_seen: is_resumed.then_some(last_seen),
+                token: Uuid::new_v4(),
+            });
+
+        let mut received = Vec::new();
+        let mut receive = |client: &mut App, server: &mut App| {
+            client.update();
+            server.update();
+            let endpoint = server.world.get::<NetworkEndpoint>(server_entity).unwrap();
+            while let Ok(NetworkRecv::NonBlocking { message }) = endpoint.try_recv() {
+                let event = EntityIdentityEvent::decode(message, endpoint.encoding()).unwrap();
+                received.push(Identity::from(event.payload.inner));
+            }
+            received.len()
+        };
+
+        let expected = if is_resumed { 1 } else { 2 };
+        testing::until(|| (receive(&mut client, &mut server) >= expected).then_some(()));
+        (0..10).for_each(|_| {
+            receive(&mut client, &mut server);
+        });
+
+        (before, after, received)
+    }
+
+    #[test]
+    fn test_resync() {
+        // Act
+        let (before, after, received) = resync_session(false);
+
+        // Assert
+        assert_eq!(received.len(), 2);
+        assert!(received.contains(&before));
+        assert!(received.contains(&after));
+    }
+
+    #[test]
+    fn test_resync_resumed() {
+        // Act
+        let (_, after, received) = resync_session(true);
+
+        // Assert
+        assert_eq!(received, [after]);
+    }
+}
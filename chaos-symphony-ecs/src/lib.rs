@@ -5,6 +5,8 @@
 
 /// Bevy Config.
 pub mod bevy_config;
+/// Callback.
+pub mod callback;
 /// Entity Identities.
 pub mod entity_identities;
 /// Entity Identity.
@@ -30,14 +32,31 @@ pub mod transformation;
 /// Types.
 pub mod types;
 
+/// Testing.
+#[cfg(test)]
+mod testing;
+
 /// Default Plugins.
 pub struct DefaultPlugins {
     /// Bevy Config.
     pub bevy_config: bevy_config::BevyConfigPlugin,
 
+    /// Network Client Config.
+    ///
+    /// Used by [`types::Role::Client`] and [`types::Role::Simulation`].
+    pub network_client_config: chaos_symphony_network::ClientConfig,
+
     /// Network Identity.
     pub network_identity: types::NetworkIdentity,
 
+    /// Network Runtime.
+    pub network_runtime: chaos_symphony_network_bevy::NetworkRuntime,
+
+    /// Network Server Config.
+    ///
+    /// Used by [`types::Role::Replication`].
+    pub network_server_config: chaos_symphony_network::ServerConfig,
+
     /// Role.
     pub role: types::Role,
 }
@@ -50,12 +69,17 @@ impl bevy::prelude::Plugin for DefaultPlugins {
         app.add_plugins((
             chaos_symphony_network_bevy::NetworkPlugin {
                 client: match self.role {
-                    types::Role::Client | types::Role::Simulation => true,
-                    types::Role::Replication => false,
+                    types::Role::Client | types::Role::Simulation => {
+                        Some(self.network_client_config.clone())
+                    }
+                    types::Role::Replication => None,
                 },
+                queue: chaos_symphony_network_bevy::QueueConfig::default()
+                    .with_overflow(chaos_symphony_network_bevy::OverflowPolicy::Coalesce),
+                runtime: self.network_runtime.clone(),
                 server: match self.role {
-                    types::Role::Client | types::Role::Simulation => false,
-                    types::Role::Replication => true,
+                    types::Role::Client | types::Role::Simulation => None,
+                    types::Role::Replication => Some(self.network_server_config.clone()),
                 },
             },
             network_authenticate::NetworkAuthenticatePlugin::new(
@@ -117,6 +141,7 @@ impl bevy::prelude::Plugin for DefaultPlugins {
             .register_type::<types::NetworkIdentity>()
             .register_type::<types::NetworkClientAuthority>()
             .register_type::<types::NetworkServerAuthority>()
+            .register_type::<types::NetworkSession>()
             .register_type::<types::Transformation>();
     }
 }
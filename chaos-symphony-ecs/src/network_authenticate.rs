@@ -1,12 +1,22 @@
-use bevy::{prelude::*, utils::Uuid};
-use chaos_symphony_async::Poll;
-use chaos_symphony_network_bevy::NetworkEndpoint;
+use bevy::{
+    ecs::{component::Tick, system::SystemChangeTick},
+    prelude::*,
+    utils::{HashMap, Uuid},
+};
+use chaos_symphony_async::PollError;
+use chaos_symphony_network::{ClientAuthentication, CloseReason};
+use chaos_symphony_network_bevy::{NetworkEndpoint, NetworkServer};
 use chaos_symphony_protocol::{
-    AuthenticateCallback, AuthenticateRequest, AuthenticateRequestPayload, AuthenticateResponse,
+    AuthenticateRequest, AuthenticateRequestPayload, AuthenticateResponse,
     AuthenticateResponsePayload, Request as _, Response as _,
 };
 
-use crate::types::{NetworkIdentity, Role, Untrusted};
+use crate::{
+    callback::{RequestFailed, ResponseReceived},
+    network_connect::NetworkTargetName,
+    network_router::NetworkAppExt as _,
+    types::{NetworkIdentity, NetworkSession, Role, Untrusted},
+};
 
 /// Network Authenticate Plugin.
 #[allow(clippy::module_name_repetitions)]
@@ -30,7 +40,9 @@ impl NetworkAuthenticatePlugin {
 impl Plugin for NetworkAuthenticatePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(self.identity.clone())
-            .add_event::<Untrusted<AuthenticateRequest>>();
+            .init_resource::<NetworkSessions>()
+            .add_network_request::<AuthenticateRequest, AuthenticateResponse>()
+            .add_systems(Update, touch);
 
         match self.role {
             Role::Client | Role::Simulation => {
@@ -43,55 +55,207 @@ impl Plugin for NetworkAuthenticatePlugin {
     }
 }
 
+/// Maximum number of authenticate requests sent per [`NetworkEndpoint`].
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Authenticate requests sent to the [`NetworkEndpoint`] so far.
+#[derive(Debug, Default, Component)]
+struct Attempts {
+    count: u32,
+}
+
+/// Maximum number of sessions remembered, forgetting the least recently seen.
+const MAX_SESSIONS: usize = 1024;
+
+/// Network Sessions.
+///
+/// Sessions by token, one per [`NetworkTargetName`] on clients and per client
+/// identity on servers.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct NetworkSessions {
+    /// Inner.
+    pub inner: HashMap<Uuid, NetworkSessionState>,
+}
+
+impl NetworkSessions {
+    /// Returns the token of the session with the key.
+    #[must_use]
+    pub fn token(&self, key: &str) -> Option<Uuid> {
+        self.inner
+            .iter()
+            .find(|(_, state)| state.key == key)
+            .map(|(token, _)| *token)
+    }
+
+    /// Returns when the session with the key and token was last seen.
+    #[must_use]
+    pub fn last_seen(&self, key: &str, token: Uuid) -> Option<Tick> {
+        self.inner
+            .get(&token)
+            .filter(|state| state.key == key)
+            .map(|state| state.last_seen)
+    }
+
+    /// Establishes the session with the token as the only one of the key.
+    ///
+    /// Forgets the least recently seen session once full.
+    fn establish(&mut self, key: String, token: Uuid, tick: Tick) {
+        self.inner.retain(|_, state| state.key != key);
+
+        if self.inner.len() >= MAX_SESSIONS {
+            let oldest = self
+                .inner
+                .iter()
+                .max_by_key(|(_, state)| tick.get().wrapping_sub(state.last_seen.get()))
+                .map(|(token, _)| *token);
+            if let Some(oldest) = oldest {
+                self.inner.remove(&oldest);
+            }
+        }
+
+        self.inner.insert(
+            token,
+            NetworkSessionState {
+                key,
+                last_seen: tick,
+            },
+        );
+    }
+}
+
+/// Network Session State.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkSessionState {
+    /// Key.
+    ///
+    /// [`NetworkTargetName`] on clients and client identity on servers.
+    pub key: String,
+
+    /// Last Seen.
+    ///
+    /// Last update with a [`NetworkSession`] using the token.
+    pub last_seen: Tick,
+}
+
+/// Touch.
+///
+/// Marks the sessions of authenticated [`NetworkEndpoint`] as seen.
+#[allow(clippy::needless_pass_by_value)]
+fn touch(
+    mut sessions: ResMut<NetworkSessions>,
+    change_tick: SystemChangeTick,
+    endpoints: Query<&NetworkSession>,
+) {
+    endpoints.for_each(|session| {
+        if let Some(state) = sessions.inner.get_mut(&session.token) {
+            state.last_seen = change_tick.this_run();
+        }
+    });
+}
+
 /// Callback.
 ///
-/// Manages [`Authenticating`] lifetime.
-/// - On ready, removes [`Authenticating`].
+/// Reacts to the [`AuthenticateResponse`].
+/// - On timeout, retries up to [`MAX_ATTEMPTS`], then closes the connection.
 /// - On error, despawns entity.
 /// - On failure, despawns entity.
-/// - On success, inserts authority.
+/// - On success, inserts authority and session.
 #[allow(clippy::needless_pass_by_value)]
-fn callback(mut commands: Commands, callbacks: Query<(Entity, &AuthenticateCallback)>) {
-    callbacks.for_each(|(entity, callback)| {
-        let span = error_span!("callback", message_id =% callback.id());
+fn callback(
+    mut commands: Commands,
+    mut sessions: ResMut<NetworkSessions>,
+    change_tick: SystemChangeTick,
+    identity: Res<NetworkIdentity>,
+    mut received: EventReader<ResponseReceived<AuthenticateResponse>>,
+    mut failed: EventReader<RequestFailed<AuthenticateResponse>>,
+    endpoints: Query<(
+        &NetworkEndpoint,
+        Option<&NetworkTargetName>,
+        Option<&Attempts>,
+    )>,
+) {
+    failed.read().for_each(|failed| {
+        let span = error_span!("callback", message_id =% failed.id);
         let _guard = span.enter();
 
-        if let Poll::Ready(result) = callback.try_poll() {
-            let mut commands = commands.entity(entity);
+        let Ok((endpoint, target_name, attempts)) = endpoints.get(failed.entity) else {
+            return;
+        };
 
-            let response = match result {
-                Ok(result) => {
-                    commands.remove::<AuthenticateCallback>();
-                    result
-                }
-                Err(error) => {
-                    error!(error =? error, "failed to authenticate");
-                    commands.despawn();
-                    return;
+        if let PollError::TimedOut = failed.error {
+            let attempts = attempts.map_or(1, |attempts| attempts.count);
+            if attempts >= MAX_ATTEMPTS {
+                error!(attempts, "authentication timed out, closing");
+                if let Err(error) = endpoint.try_close(
+                    CloseReason::AuthenticationFailed,
+                    "authentication timed out",
+                ) {
+                    warn!(error =% error, "failed to close endpoint");
                 }
-            };
-
-            let AuthenticateResponsePayload::Success {
-                client_identity,
-                server_identity,
-            } = response.payload
-            else {
-                error!("failed to authenticate");
-                commands.despawn();
                 return;
-            };
-
-            info!(
-                client_identity =% client_identity,
-                server_identity =% server_identity,
-                "authenticated"
-            );
-
-            let network_identity = NetworkIdentity {
-                inner: server_identity.into(),
-            };
-            commands.insert(network_identity);
+            }
+
+            warn!(attempts, "authentication timed out, retrying");
+            let session = session(&sessions, target_name);
+            authenticate(&mut commands, &identity, session, failed.entity, endpoint);
+            commands.entity(failed.entity).insert(Attempts {
+                count: attempts + 1,
+            });
+            return;
         }
+
+        error!(error =% failed.error, "failed to authenticate");
+        commands.entity(failed.entity).despawn();
+    });
+
+    received.read().for_each(|received| {
+        let span = error_span!("callback", message_id =% received.inner.id);
+        let _guard = span.enter();
+
+        let Ok((_, target_name, _)) = endpoints.get(received.entity) else {
+            return;
+        };
+
+        let mut commands = commands.entity(received.entity);
+        commands.remove::<Attempts>();
+
+        let AuthenticateResponsePayload::Success {
+            client_identity,
+            is_resumed,
+            server_identity,
+            session,
+        } = received.inner.payload.clone()
+        else {
+            error!("failed to authenticate");
+            commands.despawn();
+            return;
+        };
+
+        info!(
+            client_identity =% client_identity,
+            is_resumed,
+            server_identity =% server_identity,
+            "authenticated"
+        );
+
+        let last_seen = target_name.and_then(|target_name| {
+            let last_seen = sessions
+                .last_seen(&target_name.inner, session)
+                .filter(|_| is_resumed);
+            sessions.establish(target_name.inner.clone(), session, change_tick.this_run());
+            last_seen
+        });
+
+        let network_identity = NetworkIdentity {
+            inner: server_identity.into(),
+        };
+        commands.insert((
+            network_identity,
+            NetworkSession {
+                last_seen,
+                token: session,
+            },
+        ));
     });
 }
 
@@ -102,31 +266,65 @@ fn callback(mut commands: Commands, callbacks: Query<(Entity, &AuthenticateCallb
 fn initiate(
     mut commands: Commands,
     identity: Res<NetworkIdentity>,
-    endpoints: Query<(Entity, &NetworkEndpoint), Added<NetworkEndpoint>>,
+    sessions: Res<NetworkSessions>,
+    endpoints: Query<
+        (Entity, &NetworkEndpoint, Option<&NetworkTargetName>),
+        Added<NetworkEndpoint>,
+    >,
 ) {
-    endpoints.for_each(|(entity, endpoint)| {
-        let request = AuthenticateRequest::message(
-            Uuid::new_v4(),
-            AuthenticateRequestPayload {
-                identity: identity.inner.clone().into(),
-            },
-        );
+    endpoints.for_each(|(entity, endpoint, target_name)| {
+        let session = session(&sessions, target_name);
+        authenticate(&mut commands, &identity, session, entity, endpoint);
+    });
+}
 
-        match request.try_send(endpoint) {
-            Ok(authenticating) => {
-                commands.entity(entity).insert(authenticating);
-            }
-            Err(error) => {
-                warn!(error =? error, "unable to send authenticate request");
-            }
+/// Returns the session token to resume for the target.
+fn session(sessions: &NetworkSessions, target_name: Option<&NetworkTargetName>) -> Option<Uuid> {
+    target_name.and_then(|target_name| sessions.token(&target_name.inner))
+}
+
+/// Sends an authenticate request, replacing any pending
+/// [`AuthenticateCallback`](chaos_symphony_protocol::AuthenticateCallback).
+fn authenticate(
+    commands: &mut Commands,
+    identity: &NetworkIdentity,
+    session: Option<Uuid>,
+    entity: Entity,
+    endpoint: &NetworkEndpoint,
+) {
+    let request = AuthenticateRequest::message(
+        Uuid::new_v4(),
+        AuthenticateRequestPayload {
+            identity: identity.inner.clone().into(),
+            session,
+        },
+    );
+
+    match request.try_send(endpoint) {
+        Ok(authenticating) => {
+            commands.entity(entity).insert(authenticating);
         }
-    });
+        Err(error) => {
+            warn!(error =? error, "unable to send authenticate request");
+        }
+    }
 }
 
+/// Request.
+///
+/// Authenticates [`NetworkEndpoint`] using the claimed identity.
+/// - On certificate mismatch, or a trusted identity without certificate while
+///   client authentication is enabled, responds with failure and closes the
+///   connection.
+/// - On success, inserts [`NetworkIdentity`] and [`NetworkSession`], resuming
+///   the requested session if it is the latest of the identity.
 #[allow(clippy::needless_pass_by_value)]
 fn request(
     mut commands: Commands,
+    mut sessions: ResMut<NetworkSessions>,
+    change_tick: SystemChangeTick,
     identity: Res<NetworkIdentity>,
+    server: Option<Res<NetworkServer>>,
     mut reader: EventReader<Untrusted<AuthenticateRequest>>,
     endpoints: Query<(Entity, &NetworkEndpoint)>,
 ) {
@@ -150,21 +348,342 @@ fn request(
         let mut commands = commands.entity(entity);
         let payload = &request.inner.payload;
 
+        let key = payload.identity.to_string();
+        let is_trusted = matches!(payload.identity.noun.as_str(), "replication" | "simulation");
+        let is_client_authenticated = server.as_ref().is_some_and(|server| {
+            !matches!(server.client_authentication(), ClientAuthentication::None)
+        });
+
+        let reason = match endpoint.peer_certificate() {
+            Some(peer_certificate) if !peer_certificate.matches(&key) => {
+                warn!(
+                    identity = key,
+                    subject = peer_certificate.subject(),
+                    "identity does not match certificate"
+                );
+                Some("identity does not match certificate")
+            }
+            None if is_trusted && is_client_authenticated => {
+                warn!(identity = key, "identity requires certificate");
+                Some("identity requires certificate")
+            }
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            reject(endpoint, request.inner.id, reason);
+            return;
+        }
+
+        let last_seen = payload
+            .session
+            .and_then(|session| sessions.last_seen(&key, session));
+        let session = payload
+            .session
+            .filter(|_| last_seen.is_some())
+            .unwrap_or_else(Uuid::new_v4);
+        let is_resumed = last_seen.is_some();
+        sessions.establish(key, session, change_tick.this_run());
+
         let network_identity = NetworkIdentity {
             inner: payload.identity.clone().into(),
         };
-        info!(network_identity =? network_identity, "authenticated");
-        commands.insert(network_identity);
+        info!(network_identity =? network_identity, is_resumed, "authenticated");
+        commands.insert((
+            network_identity,
+            NetworkSession {
+                last_seen,
+                token: session,
+            },
+        ));
 
         let response = AuthenticateResponse::message(
             request.inner.id,
             AuthenticateResponsePayload::Success {
                 client_identity: payload.identity.clone(),
+                is_resumed,
                 server_identity: identity.inner.clone().into(),
+                session,
             },
         );
-        if let Err(error) = endpoint.try_send_non_blocking(response.into()) {
+        if let Err(error) = response.try_send(endpoint) {
             warn!(error =? error, "failed to send response to endpoint");
         }
     });
 }
+
+/// Rejects the authenticate request, closing the [`NetworkEndpoint`].
+fn reject(endpoint: &NetworkEndpoint, request_id: Uuid, reason: &str) {
+    let response = AuthenticateResponse::message(request_id, AuthenticateResponsePayload::Failure);
+    if let Err(error) = response.try_send(endpoint) {
+        warn!(error =? error, "failed to send response to endpoint");
+    }
+    if let Err(error) = endpoint.try_close(CloseReason::AuthenticationFailed, reason) {
+        warn!(error =? error, "failed to close endpoint");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::component::Tick, prelude::*, time::TimePlugin, utils::Uuid};
+    use chaos_symphony_async::Poll;
+    use chaos_symphony_network::{
+        CertificateSource, ClientAuthentication, CloseReason, MemoryNetwork, RootCertificateSource,
+    };
+    use chaos_symphony_network_bevy::NetworkEndpoint;
+    use chaos_symphony_protocol::{
+        AuthenticateRequest, AuthenticateRequestPayload, AuthenticateResponsePayload, Identity,
+        Request as _,
+    };
+
+    use crate::{
+        network_authenticate::{NetworkAuthenticatePlugin, NetworkSessions, MAX_SESSIONS},
+        network_connect::NetworkTargetName,
+        network_router::NetworkRouter,
+        testing,
+        types::{self, NetworkIdentity, NetworkSession, Role},
+    };
+
+    /// Creates a [`NetworkIdentity`] with the noun.
+    fn network_identity(noun: &str) -> NetworkIdentity {
+        NetworkIdentity {
+            inner: types::Identity {
+                id: Uuid::new_v4(),
+                noun: noun.to_string(),
+            },
+        }
+    }
+
+    /// Connects and authenticates, returning the client and server sessions.
+    fn authenticate(client: &mut App, server: &mut App) -> (NetworkSession, NetworkSession) {
+        let (client_entity, server_entity) = testing::connect(client, server);
+        client
+            .world
+            .entity_mut(client_entity)
+            .insert(NetworkTargetName {
+                inner: "default".to_string(),
+            });
+
+        testing::until(|| {
+            server.update();
+            client.update();
+            let client_session = client.world.get::<NetworkSession>(client_entity)?;
+            let server_session = server.world.get::<NetworkSession>(server_entity)?;
+            Some((*client_session, *server_session))
+        })
+    }
+
+    #[test]
+    fn test_resume() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let mut client = App::new();
+        client.add_plugins((
+            TimePlugin,
+            testing::client(&network),
+            NetworkAuthenticatePlugin::new(network_identity("simulation"), Role::Simulation),
+        ));
+
+        let mut server = App::new();
+        server.add_plugins((
+            TimePlugin,
+            testing::server(&network),
+            NetworkRouter,
+            NetworkAuthenticatePlugin::new(network_identity("replication"), Role::Replication),
+        ));
+
+        // Act
+        let (first_client, first_server) = authenticate(&mut client, &mut server);
+        let (second_client, second_server) = authenticate(&mut client, &mut server);
+
+        // Assert
+        assert!(!first_client.is_resumed());
+        assert!(!first_server.is_resumed());
+        assert_eq!(first_client.token, first_server.token);
+        assert!(second_client.is_resumed());
+        assert!(second_server.is_resumed());
+        assert_eq!(second_client.token, first_client.token);
+        assert_eq!(second_server.token, first_server.token);
+    }
+
+    /// Requests authentication as the identity, presenting a certificate for
+    /// the name if any, returning the response and the close reason.
+    fn request(
+        identity: &NetworkIdentity,
+        certificate_name: Option<&str>,
+    ) -> (AuthenticateResponsePayload, Option<CloseReason>) {
+        let network = MemoryNetwork::new();
+
+        let mut client_plugin = testing::client(&network);
+        if let Some(certificate_name) = certificate_name {
+            let certificate = CertificateSource::generate(vec![certificate_name.to_string()]);
+            client_plugin.client = client_plugin
+                .client
+                .map(|config| config.with_certificate(certificate.unwrap()));
+        }
+        let mut client = App::new();
+        client.add_plugins((TimePlugin, client_plugin));
+
+        let mut server_plugin = testing::server(&network);
+        server_plugin.server = server_plugin.server.map(|config| {
+            config.with_client_authentication(ClientAuthentication::Optional(
+                RootCertificateSource::Memory(Vec::new()),
+            ))
+        });
+        let mut server = App::new();
+        server.add_plugins((
+            TimePlugin,
+            server_plugin,
+            NetworkRouter,
+            NetworkAuthenticatePlugin::new(network_identity("replication"), Role::Replication),
+        ));
+
+        let (client_entity, _) = testing::connect(&mut client, &mut server);
+        let endpoint = client.world.get::<NetworkEndpoint>(client_entity).unwrap();
+        let authenticating = AuthenticateRequest::message(
+            Uuid::new_v4(),
+            AuthenticateRequestPayload {
+                identity: identity.inner.clone().into(),
+                session: None,
+            },
+        )
+        .try_send(endpoint)
+        .unwrap();
+
+        let response = testing::until(|| {
+            server.update();
+            client.update();
+            match authenticating.try_poll() {
+                Poll::Ready(result) => Some(result.unwrap().payload),
+                Poll::Pending => None,
+            }
+        });
+        let close_reason = testing::until(|| {
+            server.update();
+            client.update();
+            let endpoint = client.world.get::<NetworkEndpoint>(client_entity).unwrap();
+            match response {
+                AuthenticateResponsePayload::Failure => endpoint.close_reason().map(Some),
+                AuthenticateResponsePayload::Success { .. } => Some(endpoint.close_reason()),
+            }
+        });
+        (response, close_reason)
+    }
+
+    #[test]
+    fn test_request_certificate_mismatch() {
+        // Arrange
+        let identity = network_identity("simulation");
+        let other = Identity::from(network_identity("simulation").inner).to_string();
+
+        // Act
+        let (response, close_reason) = request(&identity, Some(&other));
+
+        // Assert
+        assert!(matches!(response, AuthenticateResponsePayload::Failure));
+        assert_eq!(close_reason, Some(CloseReason::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_request_certificate_required_for_trusted() {
+        // Arrange
+        let identity = network_identity("simulation");
+
+        // Act
+        let (response, close_reason) = request(&identity, None);
+
+        // Assert
+        assert!(matches!(response, AuthenticateResponsePayload::Failure));
+        assert_eq!(close_reason, Some(CloseReason::AuthenticationFailed));
+    }
+
+    #[test]
+    fn test_request_certificate_optional_for_client() {
+        // Arrange
+        let identity = network_identity("client");
+
+        // Act
+        let (response, close_reason) = request(&identity, None);
+
+        // Assert
+        assert!(matches!(
+            response,
+            AuthenticateResponsePayload::Success { .. }
+        ));
+        assert_eq!(close_reason, None);
+    }
+
+    #[test]
+    fn test_request_certificate_matches() {
+        // Arrange
+        let identity = network_identity("simulation");
+        let name = Identity::from(identity.inner.clone()).to_string();
+
+        // Act
+        let (response, close_reason) = request(&identity, Some(&name));
+
+        // Assert
+        assert!(matches!(
+            response,
+            AuthenticateResponsePayload::Success { .. }
+        ));
+        assert_eq!(close_reason, None);
+    }
+
+    #[test]
+    fn test_sessions_last_seen() {
+        // Arrange
+        let mut sessions = NetworkSessions::default();
+        let token = Uuid::new_v4();
+
+        // Act
+        sessions.establish("simulation".to_string(), token, Tick::new(1));
+
+        // Assert
+        assert_eq!(sessions.token("simulation"), Some(token));
+        assert_eq!(sessions.last_seen("simulation", token), Some(Tick::new(1)));
+        assert_eq!(sessions.last_seen("client", token), None);
+        assert_eq!(sessions.last_seen("simulation", Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_sessions_establish_replaces_key() {
+        // Arrange
+        let mut sessions = NetworkSessions::default();
+        let previous = Uuid::new_v4();
+        let token = Uuid::new_v4();
+        sessions.establish("simulation".to_string(), previous, Tick::new(1));
+
+        // Act
+        sessions.establish("simulation".to_string(), token, Tick::new(2));
+
+        // Assert
+        assert_eq!(sessions.inner.len(), 1);
+        assert_eq!(sessions.last_seen("simulation", previous), None);
+        assert_eq!(sessions.last_seen("simulation", token), Some(Tick::new(2)));
+    }
+
+    #[test]
+    fn test_sessions_forget_least_recently_seen() {
+        // Arrange
+        let mut sessions = NetworkSessions::default();
+        let tokens: Vec<_> = (0..MAX_SESSIONS)
+            .map(|index| {
+                let token = Uuid::new_v4();
+                let tick = Tick::new(u32::try_from(index).unwrap());
+                sessions.establish(index.to_string(), token, tick);
+                token
+            })
+            .collect();
+        let latest = u32::try_from(MAX_SESSIONS).unwrap();
+        sessions.inner.get_mut(&tokens[0]).unwrap().last_seen = Tick::new(latest);
+
+        // Act
+        sessions.establish("new".to_string(), Uuid::new_v4(), Tick::new(latest + 1));
+
+        // Assert
+        assert_eq!(sessions.inner.len(), MAX_SESSIONS);
+        assert!(sessions.last_seen("0", tokens[0]).is_some());
+        assert!(sessions.last_seen("1", tokens[1]).is_none());
+    }
+}
@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use std::{net::SocketAddr, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
 use chaos_symphony_async::Poll;
 use chaos_symphony_network_bevy::{Connecting, NetworkClient, NetworkEndpoint};
 
@@ -22,70 +24,685 @@ impl Plugin for NetworkConnectPlugin {
     fn build(&self, app: &mut App) {
         match self.role {
             Role::Client | Role::Simulation => {
-                app.add_systems(Update, (connect, connecting));
+                app.add_event::<ConnectAttempt>()
+                    .init_resource::<NetworkTargets>()
+                    .init_resource::<ReconnectPolicy>()
+                    .init_resource::<ReconnectState>()
+                    .register_type::<ReconnectPolicy>()
+                    .register_type::<ReconnectState>()
+                    .register_type::<ReconnectTargetState>()
+                    .add_systems(Update, (connect, connecting));
             }
             Role::Replication => {}
         }
     }
 }
 
+/// Network Targets.
+///
+/// Remote endpoints to keep connections to.
+#[derive(Debug, Clone, Resource)]
+pub struct NetworkTargets {
+    inner: Vec<NetworkTarget>,
+}
+
+impl Default for NetworkTargets {
+    /// Single connection to the remote address of the
+    /// [`ClientConfig`](chaos_symphony_network::ClientConfig).
+    fn default() -> Self {
+        Self {
+            inner: vec![NetworkTarget::new("default")],
+        }
+    }
+}
+
+impl NetworkTargets {
+    /// Creates a new [`NetworkTargets`] without targets.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    /// Returns the targets of this [`NetworkTargets`].
+    #[must_use]
+    pub fn targets(&self) -> &[NetworkTarget] {
+        &self.inner
+    }
+
+    /// With target.
+    #[must_use]
+    pub fn with_target(mut self, target: NetworkTarget) -> Self {
+        self.inner.push(target);
+        self
+    }
+
+    /// Fails over the target with the name to its next address.
+    ///
+    /// Only when the failed address is still the current one, so concurrent
+    /// failures against the same address fail over once.
+    fn fail_over(&mut self, name: &str, address: Option<SocketAddr>) {
+        self.inner
+            .iter_mut()
+            .filter(|target| target.name == name && target.address() == address)
+            .for_each(NetworkTarget::fail_over);
+    }
+}
+
+/// Network Target.
+///
+/// Named remote endpoint with the desired number of connections.
+#[derive(Debug, Clone)]
+pub struct NetworkTarget {
+    addresses: Vec<SocketAddr>,
+    count: usize,
+    cursor: usize,
+    name: String,
+    server_name: Option<String>,
+}
+
+impl NetworkTarget {
+    /// Creates a new [`NetworkTarget`].
+    ///
+    /// Connects once to the remote address of the
+    /// [`ClientConfig`](chaos_symphony_network::ClientConfig) until addresses
+    /// are added.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            addresses: Vec::new(),
+            count: 1,
+            cursor: 0,
+            name: name.into(),
+            server_name: None,
+        }
+    }
+
+    /// Returns the address connections are currently made to.
+    #[must_use]
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.addresses.get(self.cursor).copied()
+    }
+
+    /// Returns the addresses of this [`NetworkTarget`].
+    ///
+    /// Failed connections fail over to the next address, in order.
+    #[must_use]
+    pub fn addresses(&self) -> &[SocketAddr] {
+        &self.addresses
+    }
+
+    /// Returns the count of this [`NetworkTarget`].
+    ///
+    /// Number of connections to keep.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the name of this [`NetworkTarget`].
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the server name of this [`NetworkTarget`].
+    ///
+    /// Falls back to the server name of the
+    /// [`ClientConfig`](chaos_symphony_network::ClientConfig) when absent.
+    #[must_use]
+    pub fn server_name(&self) -> Option<&str> {
+        self.server_name.as_deref()
+    }
+
+    /// With address.
+    #[must_use]
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// With count.
+    #[must_use]
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// With server name.
+    #[must_use]
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+
+    /// Fails over to the next address.
+    fn fail_over(&mut self) {
+        if !self.addresses.is_empty() {
+            self.cursor = (self.cursor + 1) % self.addresses.len();
+        }
+    }
+}
+
+/// Reconnect Policy.
+///
+/// Delays connection attempts to a target after consecutive failures.
+#[derive(Debug, Clone, PartialEq, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ReconnectPolicy {
+    initial_delay: Duration,
+    jitter: f32,
+    max_attempts: Option<u32>,
+    max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            jitter: 0.2,
+            max_attempts: None,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Returns the backoff after consecutive failures, without jitter.
+    ///
+    /// Doubles the initial delay after each failure, up to the max delay.
+    #[must_use]
+    pub fn backoff(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+
+    /// Returns the delay after consecutive failures, with jitter.
+    #[must_use]
+    pub fn delay(&self, failures: u32) -> Duration {
+        let backoff = self.backoff(failures);
+        let jitter = f64::from(self.jitter.clamp(0.0, 1.0)) * rand::random::<f64>();
+        Duration::try_from_secs_f64(backoff.as_secs_f64() * (1.0 - jitter))
+            .map_or(backoff, |delay| delay.min(backoff))
+    }
+
+    /// Returns the initial delay of this [`ReconnectPolicy`].
+    #[must_use]
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Returns the jitter of this [`ReconnectPolicy`].
+    ///
+    /// Fraction of the delay randomly taken off, between 0 and 1.
+    #[must_use]
+    pub fn jitter(&self) -> f32 {
+        self.jitter
+    }
+
+    /// Returns the max attempts of this [`ReconnectPolicy`].
+    ///
+    /// Consecutive failed attempts after which a target is given up on,
+    /// unlimited when absent.
+    #[must_use]
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Returns the max delay of this [`ReconnectPolicy`].
+    #[must_use]
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// With initial delay.
+    #[must_use]
+    pub fn with_initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// With jitter.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// With max attempts.
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// With max delay.
+    #[must_use]
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+/// Reconnect State.
+///
+/// [`ReconnectPolicy`] state of each [`NetworkTarget`] by name.
+#[derive(Debug, Clone, Default, PartialEq, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ReconnectState {
+    /// Inner.
+    pub inner: HashMap<String, ReconnectTargetState>,
+}
+
+/// Reconnect Target State.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Reflect)]
+pub struct ReconnectTargetState {
+    /// Failures.
+    ///
+    /// Consecutive failed attempts.
+    pub failures: u32,
+
+    /// Is Exhausted.
+    ///
+    /// Whether max attempts was reached and the target is given up on.
+    pub is_exhausted: bool,
+
+    /// Next Attempt.
+    ///
+    /// Elapsed [`Time`] before which no attempt is made.
+    pub next_attempt: Duration,
+}
+
+/// Connect Attempt.
+///
+/// Sent when a connection attempt to a [`NetworkTarget`] resolves.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct ConnectAttempt {
+    /// Attempt.
+    ///
+    /// Consecutive attempt, starting at 1.
+    pub attempt: u32,
+
+    /// Name.
+    pub name: String,
+
+    /// Outcome.
+    pub outcome: ConnectOutcome,
+}
+
+/// Connect Outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectOutcome {
+    /// Connected.
+    Connected,
+
+    /// Failed.
+    Failed {
+        /// Retry In.
+        ///
+        /// Absent when max attempts was reached.
+        retry_in: Option<Duration>,
+    },
+}
+
+/// Network Target Name.
+///
+/// Name of the [`NetworkTarget`] a [`NetworkEndpoint`] belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct NetworkTargetName {
+    /// Inner.
+    pub inner: String,
+}
+
+/// Network Target Address.
+///
+/// Address of the [`NetworkTarget`] a [`Connecting`] was made to.
+#[derive(Debug, Clone, Copy, Component)]
+struct NetworkTargetAddress {
+    inner: Option<SocketAddr>,
+}
+
+/// Network Target Attempt.
+///
+/// Consecutive attempt of the [`NetworkTarget`] a [`Connecting`] belongs to,
+/// shared by all connections initiated in the same round.
+#[derive(Debug, Clone, Copy, Component)]
+struct NetworkTargetAttempt {
+    inner: u32,
+}
+
 /// Connect.
 ///
-/// Initiates connections when connection pool drops below 1.
+/// Initiates connections when a target drops below its count, unless held
+/// back by the [`ReconnectPolicy`].
 #[allow(clippy::needless_pass_by_value)]
 fn connect(
     mut commands: Commands,
     client: Res<NetworkClient>,
-    callbacks: Query<(), With<Connecting>>,
-    endpoints: Query<(), With<NetworkEndpoint>>,
+    state: Res<ReconnectState>,
+    targets: Res<NetworkTargets>,
+    time: Res<Time>,
+    callbacks: Query<&NetworkTargetName, With<Connecting>>,
+    endpoints: Query<&NetworkTargetName, With<NetworkEndpoint>>,
 ) {
-    let connections = callbacks.iter().count() + endpoints.iter().count();
-    for _ in connections..1 {
-        if let Ok(connecting) = client.connect() {
-            commands.spawn(connecting);
-        } else {
-            error!("failed to initiate connect");
+    targets.targets().iter().for_each(|target| {
+        let target_state = state.inner.get(target.name());
+        if let Some(target_state) = target_state {
+            if target_state.is_exhausted || time.elapsed() < target_state.next_attempt {
+                return;
+            }
         }
-    }
+        let attempt = target_state.map_or(0, |target_state| target_state.failures) + 1;
+
+        let connections = callbacks
+            .iter()
+            .chain(endpoints.iter())
+            .filter(|target_name| target_name.inner == target.name())
+            .count();
+
+        for _ in connections..target.count() {
+            let result = match target.address() {
+                Some(remote_address) => client.connect_to(
+                    remote_address,
+                    target.server_name().unwrap_or(client.server_name()),
+                ),
+                None => client.connect(),
+            };
+
+            if let Ok(connecting) = result {
+                commands.spawn((
+                    connecting,
+                    NetworkTargetAddress {
+                        inner: target.address(),
+                    },
+                    NetworkTargetAttempt { inner: attempt },
+                    NetworkTargetName {
+                        inner: target.name().to_string(),
+                    },
+                ));
+            } else {
+                error!(name = target.name(), "failed to initiate connect");
+            }
+        }
+    });
 }
 
 /// Connecting.
 ///
 /// Manages [`Connecting`] lifetime.
 ///
-/// - On success, spawns [`NetworkEndpoint`].
-/// - On ready, despawns [`Connecting`].
+/// - On success, spawns [`NetworkEndpoint`] tagged with its target.
+/// - On failure, fails over to the next address of its target and backs off.
+/// - On ready, despawns [`Connecting`] and sends [`ConnectAttempt`].
 #[allow(clippy::needless_pass_by_value)]
-fn connecting(mut commands: Commands, callbacks: Query<(Entity, &Connecting)>) {
-    callbacks.for_each(|(entity, callback)| {
+fn connecting(
+    mut commands: Commands,
+    mut state: ResMut<ReconnectState>,
+    mut targets: ResMut<NetworkTargets>,
+    mut writer: EventWriter<ConnectAttempt>,
+    policy: Res<ReconnectPolicy>,
+    time: Res<Time>,
+    callbacks: Query<(
+        Entity,
+        &Connecting,
+        &NetworkTargetAddress,
+        &NetworkTargetAttempt,
+        &NetworkTargetName,
+    )>,
+) {
+    callbacks.for_each(|(entity, callback, target_address, target_attempt, target_name)| {
         if let Poll::Ready(result) = callback.try_poll() {
             commands.entity(entity).despawn();
 
+            let target_state = state.inner.entry(target_name.inner.clone()).or_default();
+            let attempt = target_attempt.inner;
+
             let result = match result {
-                Ok(result) => result,
-                Err(error) => {
-                    error!(error =? error, "failed to connect");
-                    return;
-                }
+                Ok(Ok(endpoint)) => Ok(endpoint),
+                Ok(Err(error)) => Err(error.to_string()),
+                Err(error) => Err(error.to_string()),
             };
 
             let endpoint = match result {
-                Ok(result) => result,
+                Ok(endpoint) => endpoint,
                 Err(error) => {
-                    error!(error =? error, "failed to connect");
+                    targets.fail_over(&target_name.inner, target_address.inner);
+
+                    // connections of the same round fail as one attempt.
+                    if attempt > target_state.failures {
+                        target_state.failures = attempt;
+                        if policy
+                            .max_attempts()
+                            .is_some_and(|max_attempts| attempt >= max_attempts)
+                        {
+                            target_state.is_exhausted = true;
+                        } else {
+                            target_state.next_attempt = time.elapsed() + policy.delay(attempt);
+                        }
+                    }
+                    let retry_in = (!target_state.is_exhausted)
+                        .then(|| target_state.next_attempt.saturating_sub(time.elapsed()));
+
+                    error!(error =% error, name = target_name.inner, attempt, retry_in =? retry_in, "failed to connect");
+                    writer.send(ConnectAttempt {
+                        attempt,
+                        name: target_name.inner.clone(),
+                        outcome: ConnectOutcome::Failed { retry_in },
+                    });
                     return;
                 }
             };
 
+            *target_state = ReconnectTargetState::default();
+            writer.send(ConnectAttempt {
+                attempt,
+                name: target_name.inner.clone(),
+                outcome: ConnectOutcome::Connected,
+            });
+
             let id = endpoint.id();
             let remote_address = endpoint.remote_address();
 
-            let entity = commands.spawn(endpoint).id();
+            let entity = commands.spawn((endpoint, target_name.clone())).id();
 
-            let span =
-                info_span!("connecting", entity =? entity, id, remote_address =% remote_address);
+            let span = info_span!("connecting", entity =? entity, id, name = target_name.inner, remote_address =% remote_address);
             let _guard = span.enter();
             info!("connected");
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, time::Duration};
+
+    use bevy::{prelude::*, time::TimePlugin};
+    use chaos_symphony_network::MemoryNetwork;
+    use chaos_symphony_network_bevy::NetworkEndpoint;
+
+    use crate::{
+        network_connect::{
+            ConnectAttempt, ConnectOutcome, NetworkConnectPlugin, NetworkTarget, NetworkTargetName,
+            NetworkTargets, ReconnectPolicy, ReconnectState,
+        },
+        testing,
+        types::Role,
+    };
+
+    /// Creates a client app connecting to the targets and a server app
+    /// listening on the [`testing::address`] of a memory network.
+    fn apps(targets: NetworkTargets, policy: ReconnectPolicy) -> (App, App) {
+        let network = MemoryNetwork::new();
+
+        let mut client = App::new();
+        client
+            .add_plugins((
+                TimePlugin,
+                testing::client(&network),
+                NetworkConnectPlugin::new(Role::Client),
+            ))
+            .insert_resource(targets)
+            .insert_resource(policy);
+
+        let mut server = App::new();
+        server.add_plugins(testing::server(&network));
+
+        (client, server)
+    }
+
+    #[test]
+    fn test_fail_over() {
+        // Arrange
+        let unreachable: SocketAddr = "[::1]:4434".parse().unwrap();
+        let reachable = testing::address();
+
+        let targets = NetworkTargets::new().with_target(
+            NetworkTarget::new("primary")
+                .with_address(unreachable)
+                .with_address(reachable)
+                .with_count(2),
+        );
+        let policy = ReconnectPolicy::default().with_initial_delay(Duration::from_secs(30));
+        let (mut app, mut server) = apps(targets, policy);
+        let mut failures = 0;
+
+        // Act
+        testing::until(|| {
+            server.update();
+            app.update();
+            failures += app
+                .world
+                .resource_mut::<Events<ConnectAttempt>>()
+                .drain()
+                .filter(|event| matches!(event.outcome, ConnectOutcome::Failed { .. }))
+                .count();
+            (failures == 2).then_some(())
+        });
+
+        // Assert
+        let targets = app.world.resource::<NetworkTargets>();
+        assert_eq!(targets.targets()[0].address(), Some(reachable));
+    }
+
+    #[test]
+    fn test_connect_tags_endpoints() {
+        // Arrange
+        let address = testing::address();
+
+        let targets = NetworkTargets::new().with_target(
+            NetworkTarget::new("primary")
+                .with_address(address)
+                .with_count(2),
+        );
+        let policy = ReconnectPolicy::default().with_initial_delay(Duration::ZERO);
+        let (mut app, mut server) = apps(targets, policy);
+
+        // Act
+        let endpoints = testing::until(|| {
+            server.update();
+            app.update();
+            let endpoints: Vec<_> = app
+                .world
+                .query::<(&NetworkEndpoint, &NetworkTargetName)>()
+                .iter(&app.world)
+                .map(|(endpoint, target_name)| {
+                    (endpoint.remote_address(), target_name.inner.clone())
+                })
+                .collect();
+            (endpoints.len() == 2).then_some(endpoints)
+        });
+
+        // Assert
+        assert!(endpoints
+            .iter()
+            .all(|endpoint| *endpoint == (address, "primary".to_string())));
+    }
+
+    #[test]
+    fn test_backoff() {
+        // Arrange
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(5));
+
+        // Act
+        let backoffs: Vec<_> = (1..=5).map(|failures| policy.backoff(failures)).collect();
+
+        // Assert
+        assert_eq!(
+            backoffs,
+            [1, 2, 4, 5, 5].map(Duration::from_secs),
+            "doubles up to the max delay"
+        );
+    }
+
+    #[test]
+    fn test_delay() {
+        // Arrange
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::from_secs(4))
+            .with_jitter(0.5);
+        let unbounded = ReconnectPolicy::default()
+            .with_initial_delay(Duration::MAX)
+            .with_jitter(1.0)
+            .with_max_delay(Duration::MAX);
+
+        // Act
+        let delays: Vec<_> = (0..100).map(|_| policy.delay(1)).collect();
+        let unbounded_delays: Vec<_> = (0..100).map(|_| unbounded.delay(u32::MAX)).collect();
+
+        // Assert
+        assert!(delays
+            .iter()
+            .all(|delay| (Duration::from_secs(2)..=Duration::from_secs(4)).contains(delay)));
+        assert!(unbounded_delays.iter().all(|delay| *delay <= Duration::MAX));
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        // Arrange
+        let unreachable: SocketAddr = "[::1]:4434".parse().unwrap();
+
+        let targets = NetworkTargets::new().with_target(
+            NetworkTarget::new("primary")
+                .with_address(unreachable)
+                .with_count(2),
+        );
+        let policy = ReconnectPolicy::default()
+            .with_initial_delay(Duration::ZERO)
+            .with_max_attempts(2);
+        let (mut app, mut server) = apps(targets, policy);
+        let mut attempts = Vec::new();
+
+        // Act
+        testing::until(|| {
+            server.update();
+            app.update();
+            attempts.extend(app.world.resource_mut::<Events<ConnectAttempt>>().drain());
+            let state = app.world.resource::<ReconnectState>();
+            state
+                .inner
+                .get("primary")
+                .is_some_and(|target_state| target_state.is_exhausted)
+                .then_some(())
+        });
+        (0..10).for_each(|_| app.update());
+        attempts.extend(app.world.resource_mut::<Events<ConnectAttempt>>().drain());
+
+        // Assert
+        let target_state = &app.world.resource::<ReconnectState>().inner["primary"];
+        assert_eq!(target_state.failures, 2);
+        assert_eq!(
+            attempts.iter().filter(|event| event.attempt == 1).count(),
+            2,
+            "both connections of the first round are one attempt"
+        );
+        assert!(attempts.iter().all(|event| event.name == "primary"));
+        assert!(attempts.iter().all(|event| event.attempt <= 2));
+        assert_eq!(
+            attempts.last().map(|event| event.outcome),
+            Some(ConnectOutcome::Failed { retry_in: None })
+        );
+    }
+}
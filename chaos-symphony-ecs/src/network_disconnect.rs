@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use chaos_symphony_network::CloseReason;
 use chaos_symphony_network_bevy::NetworkEndpoint;
 
 /// Network Disconnect Plugin.
@@ -7,22 +8,43 @@ pub struct NetworkDisconnectPlugin;
 
 impl Plugin for NetworkDisconnectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, disconnected);
+        app.add_event::<Disconnected>()
+            .add_systems(Update, disconnected);
     }
 }
 
+/// Disconnected.
+///
+/// Sent when a disconnected [`NetworkEndpoint`] is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Event)]
+pub struct Disconnected {
+    /// Entity.
+    pub entity: Entity,
+
+    /// Reason.
+    ///
+    /// Absent when the connection was lost without a reason.
+    pub reason: Option<CloseReason>,
+}
+
 /// Disconnected.
 ///
 /// Despawns disconnected [`NetworkEndpoint`].
 #[allow(clippy::needless_pass_by_value)]
-fn disconnected(mut commands: Commands, endpoints: Query<(Entity, &NetworkEndpoint)>) {
+fn disconnected(
+    mut commands: Commands,
+    mut writer: EventWriter<Disconnected>,
+    endpoints: Query<(Entity, &NetworkEndpoint)>,
+) {
     endpoints.for_each(|(entity, endpoint)| {
         let span = info_span!("disconnected", entity =? entity, id = endpoint.id(), remote_address =% endpoint.remote_address());
         let _guard = span.enter();
 
         if endpoint.is_disconnected() {
+            let reason = endpoint.close_reason();
             commands.entity(entity).despawn();
-            info!("disconnected");
+            writer.send(Disconnected { entity, reason });
+            info!(reason =? reason, "disconnected");
         }
     });
 }
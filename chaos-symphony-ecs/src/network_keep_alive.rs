@@ -4,7 +4,7 @@ use bevy::{prelude::*, utils::Uuid};
 use chaos_symphony_network_bevy::NetworkEndpoint;
 use chaos_symphony_protocol::{Event as _, PingEvent, PingEventPayload};
 
-use crate::types::Role;
+use crate::{network_router::NetworkAppExt as _, types::Role};
 
 /// Network Keep Alive Plugin.
 #[allow(clippy::module_name_repetitions)]
@@ -22,6 +22,8 @@ impl NetworkKeepAlivePlugin {
 
 impl Plugin for NetworkKeepAlivePlugin {
     fn build(&self, app: &mut App) {
+        app.add_network_event::<PingEvent>();
+
         match self.role {
             Role::Client | Role::Simulation => {
                 app.insert_resource(KeepAliveTimer::new())
@@ -67,7 +69,7 @@ fn keep_alive(
                 let _guard = span.enter();
 
                 warn!("unable to send ping");
-            };
+            }
         });
     }
 }
@@ -1,101 +1,307 @@
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+};
 
 use bevy::prelude::*;
 use chaos_symphony_network_bevy::{NetworkEndpoint, NetworkRecv};
-use chaos_symphony_protocol::{
-    AuthenticateRequest, EntityClientAuthorityEvent, EntityIdentitiesRequest, EntityIdentityEvent,
-    EntityReplicationAuthorityEvent, EntitySimulationAuthorityEvent, Event, Message, PingEvent,
-    ReplicateEntityComponentsRequest, Request as _, TransformationEvent,
+use chaos_symphony_protocol::{CancelEvent, Decode, Event, Message, Request, StreamRequest};
+
+use crate::{
+    callback::CallbackPlugin,
+    types::{NetworkIdentity, Trusted, Untrusted},
 };
 
-use crate::types::{NetworkIdentity, Trusted, Untrusted};
+/// Number of message ids remembered per endpoint to detect duplicates.
+const DEDUP_CAPACITY: usize = 1024;
 
 /// Network Router.
 pub struct NetworkRouter;
 
 impl Plugin for NetworkRouter {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, route);
+        app.init_resource::<NetworkRoutes>()
+            .register_type::<NetworkDedupWindow>()
+            .add_network_event::<CancelEvent>()
+            .add_systems(Update, route);
+    }
+}
+
+/// Route.
+///
+/// Decodes a message and dispatches it as an event.
+type Route =
+    fn(&mut Commands, &NetworkEndpoint, Option<&NetworkIdentity>, chaos_symphony_network::Message);
+
+/// Network Routes.
+///
+/// Routing table from message endpoint to the [`Trusted`] and [`Untrusted`]
+/// events its messages are dispatched as.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default, Resource)]
+pub struct NetworkRoutes {
+    inner: HashMap<&'static str, Route>,
+}
+
+impl NetworkRoutes {
+    /// Returns the endpoints of this [`NetworkRoutes`].
+    pub fn endpoints(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.inner.keys().copied()
+    }
+
+    /// Routes messages on the endpoint to [`Message<T>`].
+    fn insert<T>(&mut self, endpoint: &'static str)
+    where
+        Message<T>: Decode,
+        T: Debug + Send + Sync + 'static,
+    {
+        self.inner.insert(endpoint, decode_and_dispatch::<T>);
+    }
+}
+
+/// Network Event.
+///
+/// Implemented for every [`Event`] message, naming its payload and endpoint.
+#[allow(clippy::module_name_repetitions)]
+pub trait NetworkEvent {
+    /// Payload.
+    type Payload: Debug + Send + Sync + 'static;
+
+    /// Endpoint.
+    const ENDPOINT: &'static str;
+}
+
+impl<P> NetworkEvent for Message<P>
+where
+    Message<P>: Event<P>,
+    P: Debug + Send + Sync + 'static,
+{
+    type Payload = P;
+
+    const ENDPOINT: &'static str = <Self as Event<P>>::ENDPOINT;
+}
+
+/// Network Request.
+///
+/// Implemented for every [`Request`] message with response `U`, naming its
+/// payload and endpoint.
+#[allow(clippy::module_name_repetitions)]
+pub trait NetworkRequest<U> {
+    /// Payload.
+    type Payload: Debug + Send + Sync + 'static;
+
+    /// Endpoint.
+    const ENDPOINT: &'static str;
+}
+
+impl<P, U> NetworkRequest<U> for Message<P>
+where
+    Message<P>: Request<P, U>,
+    P: Debug + Send + Sync + 'static,
+    U: Decode,
+{
+    type Payload = P;
+
+    const ENDPOINT: &'static str = <Self as Request<P, U>>::ENDPOINT;
+}
+
+/// Network Stream Request.
+///
+/// Implemented for every [`StreamRequest`] message with responses `U`, naming
+/// its payload and endpoint.
+#[allow(clippy::module_name_repetitions)]
+pub trait NetworkStreamRequest<U> {
+    /// Payload.
+    type Payload: Debug + Send + Sync + 'static;
+
+    /// Endpoint.
+    const ENDPOINT: &'static str;
+}
+
+impl<P, U> NetworkStreamRequest<U> for Message<P>
+where
+    Message<P>: StreamRequest<P, U>,
+    P: Debug + Send + Sync + 'static,
+    U: Decode,
+{
+    type Payload = P;
+
+    const ENDPOINT: &'static str = <Self as StreamRequest<P, U>>::ENDPOINT;
+}
+
+/// Network App Ext.
+///
+/// Declares the messages an [`App`] receives, each registering its
+/// [`Trusted`] and [`Untrusted`] events and its route in [`NetworkRoutes`].
+#[allow(clippy::module_name_repetitions)]
+pub trait NetworkAppExt {
+    /// Add network event.
+    fn add_network_event<T>(&mut self) -> &mut Self
+    where
+        T: NetworkEvent,
+        Message<T::Payload>: Decode;
+
+    /// Add network request.
+    ///
+    /// Also adds the [`CallbackPlugin`] of the response.
+    fn add_network_request<T, U>(&mut self) -> &mut Self
+    where
+        T: NetworkRequest<U>,
+        Message<T::Payload>: Decode,
+        U: Decode + Send + Sync + 'static;
+
+    /// Add network stream request.
+    fn add_network_stream_request<T, U>(&mut self) -> &mut Self
+    where
+        T: NetworkStreamRequest<U>,
+        Message<T::Payload>: Decode;
+}
+
+impl NetworkAppExt for App {
+    fn add_network_event<T>(&mut self) -> &mut Self
+    where
+        T: NetworkEvent,
+        Message<T::Payload>: Decode,
+    {
+        add_network_route::<T::Payload>(self, T::ENDPOINT);
+        self
+    }
+
+    fn add_network_request<T, U>(&mut self) -> &mut Self
+    where
+        T: NetworkRequest<U>,
+        Message<T::Payload>: Decode,
+        U: Decode + Send + Sync + 'static,
+    {
+        if !self.is_plugin_added::<CallbackPlugin<U>>() {
+            self.add_plugins(CallbackPlugin::<U>::new());
+        }
+        add_network_route::<T::Payload>(self, T::ENDPOINT);
+        self
+    }
+
+    fn add_network_stream_request<T, U>(&mut self) -> &mut Self
+    where
+        T: NetworkStreamRequest<U>,
+        Message<T::Payload>: Decode,
+    {
+        add_network_route::<T::Payload>(self, T::ENDPOINT);
+        self
+    }
+}
+
+/// Registers the events of [`Message<T>`] and routes the endpoint to them.
+fn add_network_route<T>(app: &mut App, endpoint: &'static str)
+where
+    Message<T>: Decode,
+    T: Debug + Send + Sync + 'static,
+{
+    app.world
+        .get_resource_or_insert_with(NetworkRoutes::default)
+        .insert::<T>(endpoint);
+    app.add_event::<Trusted<Message<T>>>()
+        .add_event::<Untrusted<Message<T>>>();
+}
+
+/// Network Dedup Window.
+///
+/// Bounded window of message ids recently routed from a [`NetworkEndpoint`],
+/// so retransmitted or relayed messages are handled once.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default, Component, Reflect)]
+pub struct NetworkDedupWindow {
+    duplicates: u64,
+    #[reflect(ignore)]
+    ids: HashSet<String>,
+    #[reflect(ignore)]
+    order: VecDeque<String>,
+}
+
+impl NetworkDedupWindow {
+    /// Returns the number of duplicate messages dropped by this
+    /// [`NetworkDedupWindow`].
+    #[must_use]
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// Inserts the id, forgetting the oldest once full.
+    ///
+    /// Returns `false` and counts a duplicate if already present.
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            self.duplicates += 1;
+            return false;
+        }
+
+        if self.order.len() >= DEDUP_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+
+        self.ids.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        true
     }
 }
 
 #[allow(clippy::needless_pass_by_value)]
-fn route(mut commands: Commands, endpoints: Query<(&NetworkEndpoint, Option<&NetworkIdentity>)>) {
-    endpoints.for_each(|(endpoint, identity)| {
+fn route(
+    mut commands: Commands,
+    routes: Res<NetworkRoutes>,
+    mut endpoints: Query<(
+        Entity,
+        &NetworkEndpoint,
+        Option<&NetworkIdentity>,
+        Option<&mut NetworkDedupWindow>,
+    )>,
+) {
+    endpoints.for_each_mut(|(entity, endpoint, identity, dedup_window)| {
+        let mut inserted = None;
+        let dedup_window = match dedup_window {
+            Some(dedup_window) => dedup_window.into_inner(),
+            None => inserted.insert(NetworkDedupWindow::default()),
+        };
+
         while let Ok(message) = endpoint.try_recv() {
             let NetworkRecv::NonBlocking { message } = message;
-            match message.endpoint.as_str() {
-                AuthenticateRequest::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        AuthenticateRequest::from(message),
-                    );
-                }
-                EntityClientAuthorityEvent::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        EntityClientAuthorityEvent::from(message),
-                    );
-                }
-                EntityReplicationAuthorityEvent::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        EntityReplicationAuthorityEvent::from(message),
-                    );
-                }
-                EntitySimulationAuthorityEvent::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        EntitySimulationAuthorityEvent::from(message),
-                    );
-                }
-                EntityIdentitiesRequest::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        EntityIdentitiesRequest::from(message),
-                    );
-                }
-                EntityIdentityEvent::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        EntityIdentityEvent::from(message),
-                    );
-                }
-                PingEvent::ENDPOINT => {}
-                ReplicateEntityComponentsRequest::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        ReplicateEntityComponentsRequest::from(message),
-                    );
-                }
-                TransformationEvent::ENDPOINT => {
-                    dispatch(
-                        &mut commands,
-                        endpoint,
-                        identity,
-                        TransformationEvent::from(message),
-                    );
-                }
-                endpoint => warn!(endpoint, "unhandled"),
+            if !dedup_window.insert(&message.id) {
+                debug!(message_id = message.id, "dropped duplicate message");
+                continue;
             }
+
+            let Some(route) = routes.inner.get(message.endpoint.as_str()) else {
+                warn!(endpoint = message.endpoint, "unhandled");
+                continue;
+            };
+            route(&mut commands, endpoint, identity, message);
+        }
+
+        if let Some(dedup_window) = inserted {
+            commands.entity(entity).insert(dedup_window);
         }
     });
 }
 
+/// Decode and dispatch.
+///
+/// Messages that failed to decode are dropped.
+fn decode_and_dispatch<T>(
+    commands: &mut Commands,
+    endpoint: &NetworkEndpoint,
+    identity: Option<&NetworkIdentity>,
+    message: chaos_symphony_network::Message,
+) where
+    Message<T>: Decode,
+    T: Send + Sync + 'static + Debug,
+{
+    match Message::<T>::decode(message, endpoint.encoding()) {
+        Ok(message) => dispatch(commands, endpoint, identity, message),
+        Err(error) => warn!(error =? error, "failed to decode message"),
+    }
+}
+
 /// Dispatch.
 pub fn dispatch<T>(
     commands: &mut Commands,
@@ -119,8 +325,11 @@ pub fn dispatch<T>(
                     message.header.source_identity = Some(identity.inner.clone().into());
                 }
             }
-            noun => todo!("{noun}"),
-        };
+            noun => {
+                warn!(noun, "unrecognized noun");
+                return;
+            }
+        }
     } else {
         message.header.source_identity = None;
     }
@@ -141,7 +350,9 @@ pub fn dispatch<T>(
                     world.send_event(event);
                 });
             }
-            noun => todo!("{noun}"),
+            noun => {
+                warn!(noun, "unrecognized noun");
+            }
         },
         None => {
             commands.add(|world: &mut World| {
@@ -152,3 +363,147 @@ pub fn dispatch<T>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::CommandQueue, prelude::*, utils::Uuid};
+    use chaos_symphony_network::MemoryNetwork;
+    use chaos_symphony_network_bevy::NetworkEndpoint;
+    use chaos_symphony_protocol::{
+        AuthenticateRequest, AuthenticateResponse, CancelEvent, Event as _, PingEvent,
+        PingEventPayload, Request as _,
+    };
+
+    use crate::{
+        callback::ResponseReceived,
+        network_router::{
+            dispatch, NetworkAppExt as _, NetworkDedupWindow, NetworkRouter, NetworkRoutes,
+            DEDUP_CAPACITY,
+        },
+        testing,
+        types::{Identity, NetworkIdentity, Trusted, Untrusted},
+    };
+
+    #[test]
+    fn test_add_network_routes() {
+        // Arrange
+        let mut app = App::new();
+
+        // Act
+        app.add_network_event::<PingEvent>()
+            .add_network_request::<AuthenticateRequest, AuthenticateResponse>()
+            .add_plugins(NetworkRouter);
+
+        // Assert
+        let routes = app.world.resource::<NetworkRoutes>();
+        let mut endpoints: Vec<_> = routes.endpoints().collect();
+        endpoints.sort_unstable();
+        assert_eq!(
+            endpoints,
+            [
+                CancelEvent::ENDPOINT,
+                PingEvent::ENDPOINT,
+                AuthenticateRequest::ENDPOINT
+            ]
+        );
+        assert!(app.world.contains_resource::<Events<Trusted<PingEvent>>>());
+        assert!(app
+            .world
+            .contains_resource::<Events<Untrusted<AuthenticateRequest>>>());
+        assert!(app
+            .world
+            .contains_resource::<Events<ResponseReceived<AuthenticateResponse>>>());
+        assert!(app
+            .world
+            .resource::<AppTypeRegistry>()
+            .read()
+            .get(std::any::TypeId::of::<NetworkDedupWindow>())
+            .is_some());
+    }
+
+    #[test]
+    fn test_dedup_window_drops_duplicates() {
+        // Arrange
+        let mut dedup_window = NetworkDedupWindow::default();
+
+        // Act
+        let first = dedup_window.insert("1");
+        let duplicate = dedup_window.insert("1");
+        let other = dedup_window.insert("2");
+
+        // Assert
+        assert!(first);
+        assert!(!duplicate);
+        assert!(other);
+        assert_eq!(dedup_window.duplicates(), 1);
+    }
+
+    #[test]
+    fn test_dedup_window_forgets_oldest() {
+        // Arrange
+        let mut dedup_window = NetworkDedupWindow::default();
+        (0..DEDUP_CAPACITY).for_each(|id| {
+            dedup_window.insert(&id.to_string());
+        });
+
+        // Act
+        let evicting = dedup_window.insert("new");
+        let oldest = dedup_window.insert("0");
+        let retained = dedup_window.insert(&(DEDUP_CAPACITY - 1).to_string());
+
+        // Assert
+        assert!(evicting);
+        assert!(oldest, "oldest id forgotten once full");
+        assert!(!retained);
+        assert_eq!(dedup_window.duplicates(), 1);
+    }
+
+    #[test]
+    fn test_dispatch_drops_unrecognized_noun() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let mut client = App::new();
+        client.add_plugins(testing::client(&network));
+        let mut server = App::new();
+        server
+            .add_plugins((testing::server(&network), NetworkRouter))
+            .add_network_event::<PingEvent>();
+        let (_, server_entity) = testing::connect(&mut client, &mut server);
+
+        let identity = |noun: &str| Identity {
+            id: Uuid::new_v4(),
+            noun: noun.to_string(),
+        };
+        let unrecognized = NetworkIdentity {
+            inner: identity("foo"),
+        };
+        let replication = NetworkIdentity {
+            inner: identity("replication"),
+        };
+        let mut relayed = PingEvent::message(Uuid::new_v4(), PingEventPayload);
+        relayed.header.source_identity = Some(identity("foo").into());
+
+        // Act
+        let mut queue = CommandQueue::default();
+        let endpoint = server.world.get::<NetworkEndpoint>(server_entity).unwrap();
+        let mut commands = Commands::new(&mut queue, &server.world);
+        dispatch(
+            &mut commands,
+            endpoint,
+            Some(&unrecognized),
+            PingEvent::message(Uuid::new_v4(), PingEventPayload),
+        );
+        dispatch(&mut commands, endpoint, Some(&replication), relayed);
+        queue.apply(&mut server.world);
+
+        // Assert
+        assert!(server
+            .world
+            .resource::<Events<Trusted<PingEvent>>>()
+            .is_empty());
+        assert!(server
+            .world
+            .resource::<Events<Untrusted<PingEvent>>>()
+            .is_empty());
+    }
+}
@@ -1,5 +1,4 @@
 use bevy::{prelude::*, utils::Uuid};
-use chaos_symphony_async::Poll;
 use chaos_symphony_network_bevy::NetworkEndpoint;
 use chaos_symphony_protocol::{
     ReplicateEntityComponentsCallback, ReplicateEntityComponentsRequest,
@@ -7,10 +6,14 @@ use chaos_symphony_protocol::{
     ReplicateEntityComponentsResponsePayload, Request as _, Response as _,
 };
 
-use crate::types::{
-    EntityAuthority, EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority,
-    NetworkIdentity, NetworkReplicationAuthority, NetworkServerAuthority, ReplicateSink,
-    ReplicateSource, Role, Trusted, Untrusted,
+use crate::{
+    callback::{RequestFailed, ResponseReceived},
+    network_router::NetworkAppExt as _,
+    types::{
+        EntityAuthority, EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority,
+        NetworkIdentity, NetworkReplicationAuthority, NetworkServerAuthority, NetworkSession,
+        ReplicateSink, ReplicateSource, Role, Trusted, Untrusted,
+    },
 };
 
 /// Replicate Entity Components Plugin.
@@ -30,8 +33,7 @@ impl ReplicateEntityComponentsPlugin {
 
 impl Plugin for ReplicateEntityComponentsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<Trusted<ReplicateEntityComponentsRequest>>()
-            .add_event::<Untrusted<ReplicateEntityComponentsRequest>>()
+        app.add_network_request::<ReplicateEntityComponentsRequest, ReplicateEntityComponentsResponse>()
             .add_systems(Update, callback)
             .add_systems(Update, request);
 
@@ -39,13 +41,19 @@ impl Plugin for ReplicateEntityComponentsPlugin {
             Role::Client | Role::Simulation => {
                 app.add_systems(
                     Update,
-                    initiate::<EntityReplicationAuthority, NetworkReplicationAuthority>,
+                    (
+                        initiate::<EntityReplicationAuthority, NetworkReplicationAuthority>,
+                        resync::<EntityReplicationAuthority>,
+                    ),
                 );
             }
             Role::Replication => {
                 app.add_systems(
                     Update,
-                    initiate::<EntitySimulationAuthority, NetworkServerAuthority>,
+                    (
+                        initiate::<EntitySimulationAuthority, NetworkServerAuthority>,
+                        resync::<EntitySimulationAuthority>,
+                    ),
                 )
                 .add_systems(Update, validate_request);
             }
@@ -56,31 +64,30 @@ impl Plugin for ReplicateEntityComponentsPlugin {
 #[allow(clippy::needless_pass_by_value)]
 fn callback(
     mut commands: Commands,
-    callbacks: Query<(Entity, &ReplicateEntityComponentsCallback)>,
+    mut received: EventReader<ResponseReceived<ReplicateEntityComponentsResponse>>,
+    mut failed: EventReader<RequestFailed<ReplicateEntityComponentsResponse>>,
 ) {
-    callbacks.for_each(|(entity, callback)| {
-        let span = error_span!("callback", message_id =% callback.id);
+    failed.read().for_each(|failed| {
+        let span = error_span!("callback", message_id =% failed.id);
         let _guard = span.enter();
 
-        if let Poll::Ready(result) = callback.try_poll() {
-            let mut commands = commands.entity(entity);
-
-            commands.remove::<ReplicateEntityComponentsCallback>();
+        error!(error =% failed.error, "failed to receive response from server");
+    });
 
-            let Ok(response) = result else {
-                error!("failed to receive response from server");
-                return;
-            };
+    received.read().for_each(|received| {
+        let span = error_span!("callback", message_id =% received.inner.id);
+        let _guard = span.enter();
 
-            match response.payload {
-                ReplicateEntityComponentsResponsePayload::Failure => {
-                    error!("rejected by server");
-                }
-                ReplicateEntityComponentsResponsePayload::Success => {
-                    info!("accepted by server");
+        match received.inner.payload {
+            ReplicateEntityComponentsResponsePayload::Failure => {
+                error!("rejected by server");
+            }
+            ReplicateEntityComponentsResponsePayload::Success => {
+                info!("accepted by server");
+                if let Some(mut commands) = commands.get_entity(received.entity) {
                     commands.insert(ReplicateSink);
                 }
-            };
+            }
         }
     });
 }
@@ -162,11 +169,34 @@ fn request(
         if response.try_send(endpoint).is_err() {
             error!("failed to send response");
             return;
-        };
+        }
         info!("response sent");
     });
 }
 
+/// Resync.
+///
+/// Removes [`ReplicateSink`] from entities under the authority of a newly
+/// authenticated [`NetworkEndpoint`], so their components are requested again
+/// after reconnecting.
+#[allow(clippy::needless_pass_by_value)]
+fn resync<EA>(
+    mut commands: Commands,
+    endpoints: Query<&NetworkIdentity, Added<NetworkSession>>,
+    entities: Query<(Entity, &EA), With<ReplicateSink>>,
+) where
+    EA: EntityAuthority + Component,
+{
+    endpoints.for_each(|network_identity| {
+        entities
+            .iter()
+            .filter(|(_, entity_authority)| *entity_authority.identity() == network_identity.inner)
+            .for_each(|(entity, _)| {
+                commands.entity(entity).remove::<ReplicateSink>();
+            });
+    });
+}
+
 fn validate_request(
     mut reader: EventReader<Untrusted<ReplicateEntityComponentsRequest>>,
     mut writer: EventWriter<Trusted<ReplicateEntityComponentsRequest>>,
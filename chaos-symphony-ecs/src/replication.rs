@@ -1,13 +1,16 @@
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData};
 
 use bevy::prelude::*;
 use chaos_symphony_network_bevy::NetworkEndpoint;
-use chaos_symphony_protocol::{Event as _, ReplicateEntityComponentsRequest};
+use chaos_symphony_protocol::{Decode, Event as _, Message, ReplicateEntityComponentsRequest};
 
-use crate::types::{
-    EntityAuthority, EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority,
-    NetworkIdentity, NetworkReplicationAuthority, NetworkServerAuthority, ReplicateComponent,
-    ReplicateEvent, Role, Trusted, Untrusted,
+use crate::{
+    network_router::{NetworkAppExt as _, NetworkEvent},
+    types::{
+        EntityAuthority, EntityIdentity, EntityReplicationAuthority, EntitySimulationAuthority,
+        Identity, NetworkIdentity, NetworkReplicationAuthority, NetworkServerAuthority,
+        ReplicateComponent, ReplicateEvent, ReplicateSequence, Role, Trusted, Untrusted,
+    },
 };
 
 /// Replication Plugin.
@@ -37,13 +40,20 @@ impl<C, E, P> Plugin for ReplicationPlugin<C, E, P>
 where
     C: ReplicateComponent + Component,
     C::Message: chaos_symphony_protocol::Event<P>,
-    E: ReplicateEvent + Clone + Send + Sync + 'static + chaos_symphony_protocol::Event<P>,
+    E: ReplicateEvent
+        + Clone
+        + Send
+        + Sync
+        + 'static
+        + chaos_symphony_protocol::Event<P>
+        + NetworkEvent<Payload = P>,
     P: Send + Sync + 'static,
+    Message<P>: Decode,
 {
     fn build(&self, app: &mut App) {
-        app.add_event::<Trusted<E>>().add_event::<Untrusted<E>>();
+        app.add_network_event::<E>();
 
-        app.add_systems(Update, apply_trusted_event::<E>);
+        app.add_systems(Update, apply_trusted_event::<C, E>);
 
         match self.role {
             Role::Client => {
@@ -75,37 +85,63 @@ where
                 app.add_systems(Update, send_trusted_event::<E, P>);
                 app.add_systems(Update, replicate_trusted_component::<C, P>);
             }
-        };
+        }
     }
 }
 
+/// Apply Trusted Event.
+///
+/// Events older than the last one applied to the component from the same
+/// source are dropped, as events may arrive out of order.
 #[allow(clippy::needless_pass_by_value)]
-fn apply_trusted_event<E>(
+fn apply_trusted_event<C, E>(
     mut commands: Commands,
     mut reader: EventReader<Trusted<E>>,
-    query: Query<(&EntityIdentity, Entity)>,
+    query: Query<(&EntityIdentity, Entity, Option<&ReplicateSequence<C>>)>,
 ) where
+    C: Component,
     E: ReplicateEvent + Send + Sync + 'static,
 {
+    // sequences applied this frame, not yet visible to the query.
+    let mut applied = HashMap::<Entity, ReplicateSequence<C>>::new();
+
     reader.read().for_each(|trusted| {
         let span = error_span!("event", message_id =%  trusted.inner.id());
         let _guard = span.enter();
 
-        if trusted.inner.source_identity().is_none() {
+        let Some(source_identity) = trusted.inner.source_identity() else {
             // Trusted event originated from the current process.
             // Implies that the event was generated from components that have already been updated.
             return;
-        }
+        };
+        let source_identity: Identity = source_identity.clone().into();
 
-        let Some((_, entity)) = query
-            .iter()
-            .find(|(entity_identity, _)| entity_identity.inner == *trusted.inner.entity_identity())
-        else {
+        let Some((_, entity, replicate_sequence)) = query.iter().find(|(entity_identity, _, _)| {
+            entity_identity.inner == *trusted.inner.entity_identity()
+        }) else {
             warn!("entity does not exist");
             return;
         };
 
-        trusted.inner.insert_bundle(commands.entity(entity));
+        let sequence = trusted.inner.sequence();
+        if applied
+            .get(&entity)
+            .or(replicate_sequence)
+            .is_some_and(|replicate_sequence| {
+                replicate_sequence.is_stale(sequence, &source_identity)
+            })
+        {
+            debug!(sequence, "dropped stale event");
+            return;
+        }
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(ReplicateSequence::<C>::new(
+            sequence,
+            source_identity.clone(),
+        ));
+        trusted.inner.insert_bundle(entity_commands);
+        applied.insert(entity, ReplicateSequence::new(sequence, source_identity));
     });
 }
 
@@ -123,15 +159,13 @@ fn send_trusted_event<E, P>(
                 event
                     .inner
                     .source_identity()
-                    .map_or(true, |source_identity| {
-                        *source_identity != network_identity.inner
-                    })
+                    .is_none_or(|source_identity| *source_identity != network_identity.inner)
             })
             .for_each(|(endpoint, _)| {
                 let message = event.inner.clone();
                 if message.try_send(endpoint).is_err() {
                     error!("failed to send event");
-                };
+                }
             });
     });
 }
@@ -165,7 +199,7 @@ fn send_untrusted_event<E, P, EA, NA>(
         let message = event.inner.clone();
         if message.try_send(endpoint).is_err() {
             error!("failed to send event");
-        };
+        }
     });
 }
 
@@ -205,6 +239,115 @@ fn replicate_trusted_component<C, P>(
         let message = component.to_message(entity_identity);
         if message.try_send(endpoint).is_err() {
             error!("failed to send event");
-        };
+        }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        math::{DQuat, DVec3},
+        prelude::*,
+        utils::Uuid,
+    };
+    use chaos_symphony_protocol::{Event as _, TransformationEvent, TransformationEventPayload};
+
+    use crate::{
+        replication::apply_trusted_event,
+        types::{EntityIdentity, Identity, Transformation, Trusted},
+    };
+
+    /// Creates a [`Transformation`] at the position along the x axis.
+    fn transformation(x: f64) -> Transformation {
+        Transformation {
+            orientation: DQuat::IDENTITY,
+            position: DVec3::new(x, 0.0, 0.0),
+        }
+    }
+
+    /// Creates a [`Trusted<TransformationEvent>`] from the source with the
+    /// sequence and position.
+    fn event(
+        entity_identity: &Identity,
+        source_identity: &Identity,
+        sequence: u64,
+        x: f64,
+    ) -> Trusted<TransformationEvent> {
+        let mut message = TransformationEvent::message(
+            Uuid::new_v4(),
+            TransformationEventPayload {
+                entity_identity: entity_identity.clone().into(),
+                transformation: transformation(x).into(),
+            },
+        );
+        message.header.sequence = sequence;
+        message.header.source_identity = Some(source_identity.clone().into());
+        Trusted { inner: message }
+    }
+
+    /// Creates an app applying transformation events to an entity, returning
+    /// the app, entity and identities of the entity and the source.
+    fn app() -> (App, Entity, Identity, Identity) {
+        let entity_identity = Identity {
+            id: Uuid::new_v4(),
+            noun: "ship".to_string(),
+        };
+        let source_identity = Identity {
+            id: Uuid::new_v4(),
+            noun: "simulation".to_string(),
+        };
+
+        let mut app = App::new();
+        app.add_event::<Trusted<TransformationEvent>>().add_systems(
+            Update,
+            apply_trusted_event::<Transformation, TransformationEvent>,
+        );
+        let entity = app
+            .world
+            .spawn(EntityIdentity {
+                inner: entity_identity.clone(),
+            })
+            .id();
+
+        (app, entity, entity_identity, source_identity)
+    }
+
+    #[test]
+    fn test_apply_trusted_event_drops_older() {
+        // Arrange
+        let (mut app, entity, entity_identity, source_identity) = app();
+        app.world
+            .send_event(event(&entity_identity, &source_identity, 2, 2.0));
+        app.update();
+
+        // Act
+        app.world
+            .send_event(event(&entity_identity, &source_identity, 1, 1.0));
+        app.update();
+
+        // Assert
+        assert_eq!(
+            app.world.get::<Transformation>(entity),
+            Some(&transformation(2.0))
+        );
+    }
+
+    #[test]
+    fn test_apply_trusted_event_drops_older_in_same_frame() {
+        // Arrange
+        let (mut app, entity, entity_identity, source_identity) = app();
+
+        // Act
+        app.world
+            .send_event(event(&entity_identity, &source_identity, 2, 2.0));
+        app.world
+            .send_event(event(&entity_identity, &source_identity, 1, 1.0));
+        app.update();
+
+        // Assert
+        assert_eq!(
+            app.world.get::<Transformation>(entity),
+            Some(&transformation(2.0))
+        );
+    }
+}
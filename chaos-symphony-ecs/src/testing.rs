@@ -0,0 +1,81 @@
+use std::net::SocketAddr;
+
+use bevy::prelude::*;
+use chaos_symphony_async::Poll;
+use chaos_symphony_network::{
+    ClientConfig, ConnectError, MemoryNetwork, ServerConfig, TransportConfig,
+};
+pub use chaos_symphony_network_bevy::testing::until;
+use chaos_symphony_network_bevy::{
+    NetworkClient, NetworkError, NetworkPlugin, NetworkRuntime, NetworkServer,
+};
+
+/// Returns the address the server listens on.
+pub fn address() -> SocketAddr {
+    "[::1]:4433".parse().unwrap()
+}
+
+/// Creates a [`NetworkPlugin`] connecting to the [`address`] over the network.
+pub fn client(network: &MemoryNetwork) -> NetworkPlugin {
+    NetworkPlugin {
+        client: Some(
+            ClientConfig::default()
+                .with_remote_address(address())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        ),
+        queue: default(),
+        runtime: NetworkRuntime::CurrentThread,
+        server: None,
+    }
+}
+
+/// Creates a [`NetworkPlugin`] listening on the [`address`] of the network.
+pub fn server(network: &MemoryNetwork) -> NetworkPlugin {
+    NetworkPlugin {
+        client: None,
+        queue: default(),
+        runtime: NetworkRuntime::CurrentThread,
+        server: Some(
+            ServerConfig::default()
+                .with_listen_address(address())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        ),
+    }
+}
+
+/// Connects the client app to the server app, spawning the endpoint in each.
+///
+/// Returns the client and server entities.
+pub fn connect(client: &mut App, server: &mut App) -> (Entity, Entity) {
+    let mut connecting = client.world.resource::<NetworkClient>().connect().unwrap();
+    let client_endpoint = until(|| {
+        server.update();
+        client.update();
+        match connecting.try_poll() {
+            Poll::Ready(Ok(Ok(endpoint))) => Some(endpoint),
+            // the server bridge may not be listening yet.
+            Poll::Ready(Ok(Err(NetworkError::Connect {
+                error: ConnectError::Refused,
+                ..
+            }))) => {
+                connecting = client.world.resource::<NetworkClient>().connect().unwrap();
+                None
+            }
+            Poll::Ready(Ok(Err(error))) => panic!("{error}"),
+            Poll::Ready(Err(error)) => panic!("{error}"),
+            Poll::Pending => None,
+        }
+    });
+    let server_endpoint = match until(|| {
+        server.update();
+        server.world.resource::<NetworkServer>().try_recv().ok()
+    }) {
+        Ok(endpoint) => endpoint,
+        Err(error) => panic!("{error}"),
+    };
+
+    (
+        client.world.spawn(client_endpoint).id(),
+        server.world.spawn(server_endpoint).id(),
+    )
+}
@@ -47,7 +47,7 @@ fn broadcast_on_change(
 
             if message.try_send(endpoint).is_err() {
                 error!("failed to send event");
-            };
+            }
         });
     });
 }
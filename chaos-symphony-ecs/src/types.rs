@@ -1,5 +1,7 @@
+use std::marker::PhantomData;
+
 use bevy::{
-    ecs::system::EntityCommands,
+    ecs::{component::Tick, system::EntityCommands},
     math::{DQuat, DVec3},
     prelude::*,
     utils::Uuid,
@@ -13,7 +15,7 @@ use chaos_symphony_protocol::Event as _;
  */
 
 /// Identity.
-#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
 pub struct Identity {
     /// Id.
     pub id: Uuid,
@@ -120,6 +122,10 @@ impl ReplicateEvent for chaos_symphony_protocol::EntityClientAuthorityEvent {
         commands.insert(component);
     }
 
+    fn sequence(&self) -> u64 {
+        self.header.sequence
+    }
+
     fn source_identity(&self) -> Option<&chaos_symphony_protocol::Identity> {
         self.header.source_identity.as_ref()
     }
@@ -174,6 +180,10 @@ impl ReplicateEvent for chaos_symphony_protocol::EntityReplicationAuthorityEvent
         commands.insert(component);
     }
 
+    fn sequence(&self) -> u64 {
+        self.header.sequence
+    }
+
     fn source_identity(&self) -> Option<&chaos_symphony_protocol::Identity> {
         self.header.source_identity.as_ref()
     }
@@ -228,6 +238,10 @@ impl ReplicateEvent for chaos_symphony_protocol::EntitySimulationAuthorityEvent
         commands.insert(component);
     }
 
+    fn sequence(&self) -> u64 {
+        self.header.sequence
+    }
+
     fn source_identity(&self) -> Option<&chaos_symphony_protocol::Identity> {
         self.header.source_identity.as_ref()
     }
@@ -246,6 +260,30 @@ pub struct NetworkIdentity {
     pub inner: Identity,
 }
 
+/// Network Session.
+///
+/// Session established by authenticating a [`NetworkEndpoint`](chaos_symphony_network_bevy::NetworkEndpoint).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+pub struct NetworkSession {
+    /// Last Seen.
+    ///
+    /// When the session was last seen, if a session from a previous connection
+    /// was resumed.
+    #[reflect(ignore)]
+    pub last_seen: Option<Tick>,
+
+    /// Token.
+    pub token: Uuid,
+}
+
+impl NetworkSession {
+    /// Returns whether a session from a previous connection was resumed.
+    #[must_use]
+    pub fn is_resumed(&self) -> bool {
+        self.last_seen.is_some()
+    }
+}
+
 /// Network Client Authority.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Reflect)]
@@ -287,10 +325,49 @@ pub trait ReplicateEvent {
     /// Insert Bundle.
     fn insert_bundle(&self, commands: EntityCommands<'_, '_, '_>);
 
+    /// Sequence.
+    fn sequence(&self) -> u64;
+
     /// Source Identity.
     fn source_identity(&self) -> Option<&chaos_symphony_protocol::Identity>;
 }
 
+/// Replicate Sequence.
+///
+/// Sequence of the last event applied to component `C` of the entity, so
+/// older events from the same source arriving out of order are dropped.
+#[derive(Debug, Component)]
+pub struct ReplicateSequence<C> {
+    /// Sequence.
+    pub sequence: u64,
+
+    /// Source Identity.
+    pub source_identity: Identity,
+
+    marker: PhantomData<C>,
+}
+
+impl<C> ReplicateSequence<C> {
+    /// Creates a new [`ReplicateSequence`].
+    #[must_use]
+    pub fn new(sequence: u64, source_identity: Identity) -> Self {
+        Self {
+            sequence,
+            source_identity,
+            marker: PhantomData,
+        }
+    }
+
+    /// Is stale.
+    ///
+    /// Whether an event with the sequence from the source is older than the
+    /// last one applied.
+    #[must_use]
+    pub fn is_stale(&self, sequence: u64, source_identity: &Identity) -> bool {
+        self.source_identity == *source_identity && self.sequence >= sequence
+    }
+}
+
 /// Replicate Sink.
 #[derive(Debug, Clone, PartialEq, Eq, Component, Reflect)]
 pub struct ReplicateSink;
@@ -381,6 +458,10 @@ impl ReplicateEvent for chaos_symphony_protocol::TransformationEvent {
         commands.insert(component);
     }
 
+    fn sequence(&self) -> u64 {
+        self.header.sequence
+    }
+
     fn source_identity(&self) -> Option<&chaos_symphony_protocol::Identity> {
         self.header.source_identity.as_ref()
     }
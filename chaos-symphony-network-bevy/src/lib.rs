@@ -3,43 +3,115 @@
 
 //! Chaos Symphony Network Bevy
 
+mod queue;
+mod runtime;
+/// Testing.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt, io,
     net::SocketAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
         mpsc::TryRecvError,
         Arc,
     },
+    time::{Duration, Instant},
 };
 
 use bevy::{prelude::*, utils::tracing::instrument};
-use chaos_symphony_async::{Future, Poll, PollError};
-use chaos_symphony_network::{AcceptError, Client, Connection, Message, RecvError, Server};
+use chaos_symphony_async::{Clock, Future, Poll, PollError, Stream};
+use chaos_symphony_network::{
+    AcceptError, Channel, Client, ClientAuthentication, ClientConfig, CloseReason, ConnectError,
+    Connection, ConnectionStats, Encoding, Message, PeerCertificate, RecvError, Reliability,
+    Server, ServerConfig,
+};
+use futures_util::{stream::FuturesUnordered, StreamExt as _};
+
+use queue::SendQueue;
+pub use queue::{OverflowPolicy, QueueConfig};
+pub use runtime::{NetworkExecutor, NetworkRuntime};
+
+/// Interval at which expired pending requests are removed.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Network Plugin.
 #[allow(clippy::module_name_repetitions)]
 pub struct NetworkPlugin {
     /// Client.
-    pub client: bool,
+    ///
+    /// Starts a client using the config when present.
+    pub client: Option<ClientConfig>,
+
+    /// Queue.
+    ///
+    /// Bounds the messages queued per [`NetworkEndpoint`].
+    pub queue: QueueConfig,
+
+    /// Runtime.
+    ///
+    /// Runs the bevy-tokio bridge, so the app needs no runtime of its own.
+    pub runtime: NetworkRuntime,
 
     /// Server.
-    pub server: bool,
+    ///
+    /// Starts a server using the config when present.
+    pub server: Option<ServerConfig>,
 }
 
 impl Plugin for NetworkPlugin {
     fn build(&self, app: &mut App) {
-        if self.client {
+        let executor = self.runtime.start();
+        let handle = executor.handle().clone();
+        app.insert_resource(executor);
+
+        if let Some(config) = &self.client {
             let (from_bevy, to_tokio) = tokio::sync::mpsc::unbounded_channel();
-            tokio::spawn(NetworkClient::bridge(to_tokio));
-            app.insert_resource(NetworkClient::new(from_bevy));
+            handle.spawn(NetworkClient::bridge(config.clone(), self.queue, to_tokio));
+            app.insert_resource(NetworkClient::new(
+                config.server_name().to_string(),
+                from_bevy,
+            ));
         }
 
-        if self.server {
+        if let Some(config) = &self.server {
             let (from_tokio, to_bevy) = std::sync::mpsc::channel();
-            tokio::spawn(NetworkServer::bridge(from_tokio));
-            app.insert_resource(NetworkServer::new(to_bevy));
+            handle.spawn(NetworkServer::bridge(
+                config.clone(),
+                self.queue,
+                from_tokio,
+            ));
+            app.insert_resource(NetworkServer::new(
+                config.client_authentication().clone(),
+                to_bevy,
+            ));
         }
+
+        app.init_resource::<NetworkClock>()
+            .add_systems(First, advance_clock.after(bevy::time::TimeSystem))
+            .add_systems(PreUpdate, refresh_stats);
+    }
+}
+
+/// Network Clock.
+///
+/// [`Clock`] advanced from [`Time`] at the start of every frame, so timeouts
+/// created with [`FutureExt::timeout`](chaos_symphony_async::FutureExt::timeout)
+/// follow Bevy time.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default, Resource)]
+pub struct NetworkClock {
+    /// Inner.
+    pub inner: Clock,
+}
+
+/// Advances the [`NetworkClock`] to the elapsed [`Time`].
+#[allow(clippy::needless_pass_by_value)]
+fn advance_clock(clock: Res<NetworkClock>, time: Option<Res<Time>>) {
+    if let Some(time) = time {
+        clock.inner.advance_to(time.elapsed());
     }
 }
 
@@ -47,23 +119,34 @@ impl Plugin for NetworkPlugin {
 #[allow(clippy::module_name_repetitions)]
 #[derive(Resource)]
 pub struct NetworkClient {
-    sender: tokio::sync::mpsc::UnboundedSender<
-        std::sync::mpsc::Sender<Result<NetworkEndpoint, AcceptError>>,
-    >,
+    sender: tokio::sync::mpsc::UnboundedSender<ConnectRequest>,
+    server_name: String,
 }
 
 impl NetworkClient {
     /// Creates a new [`NetworkClient`].
     fn new(
-        sender: tokio::sync::mpsc::UnboundedSender<
-            std::sync::mpsc::Sender<Result<NetworkEndpoint, AcceptError>>,
-        >,
+        server_name: String,
+        sender: tokio::sync::mpsc::UnboundedSender<ConnectRequest>,
     ) -> Self {
-        Self { sender }
+        Self {
+            sender,
+            server_name,
+        }
+    }
+
+    /// Returns the server name of this [`NetworkClient`].
+    ///
+    /// Server name of the [`ClientConfig`].
+    #[must_use]
+    pub fn server_name(&self) -> &str {
+        &self.server_name
     }
 
     /// Connect.
     ///
+    /// Connects to the remote address of the [`ClientConfig`].
+    ///
     /// # Errors
     ///
     /// Will return `Err` if bevy-tokio bridge is disconnected.
@@ -72,31 +155,98 @@ impl NetworkClient {
     ) -> Result<
         Connecting,
         tokio::sync::mpsc::error::SendError<
-            std::sync::mpsc::Sender<Result<NetworkEndpoint, AcceptError>>,
+            chaos_symphony_async::Sender<Result<NetworkEndpoint, NetworkError>>,
         >,
     > {
-        let (sender, receiver) = std::sync::mpsc::channel();
-        self.sender.send(sender).map(|()| Connecting {
-            inner: Future::new(receiver),
-        })
+        self.request(None)
+    }
+
+    /// Connect to.
+    ///
+    /// Connects to the remote address, verifying the server certificate
+    /// against the server name.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected.
+    pub fn connect_to(
+        &self,
+        remote_address: SocketAddr,
+        server_name: impl Into<String>,
+    ) -> Result<
+        Connecting,
+        tokio::sync::mpsc::error::SendError<
+            chaos_symphony_async::Sender<Result<NetworkEndpoint, NetworkError>>,
+        >,
+    > {
+        self.request(Some((remote_address, server_name.into())))
+    }
+
+    /// Requests a connection from the bevy-tokio bridge.
+    fn request(
+        &self,
+        remote: Option<(SocketAddr, String)>,
+    ) -> Result<
+        Connecting,
+        tokio::sync::mpsc::error::SendError<
+            chaos_symphony_async::Sender<Result<NetworkEndpoint, NetworkError>>,
+        >,
+    > {
+        let (sender, receiver) = chaos_symphony_async::channel();
+        self.sender
+            .send(ConnectRequest { remote, sender })
+            .map(|()| Connecting {
+                inner: Future::new(receiver),
+            })
+            .map_err(|tokio::sync::mpsc::error::SendError(request)| {
+                tokio::sync::mpsc::error::SendError(request.sender)
+            })
     }
 
     /// Bridges bevy-tokio runtime using channels.
-    #[instrument(name = "network_client", skip(receiver))]
+    #[instrument(name = "network_client", skip(config, queue, receiver))]
     async fn bridge(
-        mut receiver: tokio::sync::mpsc::UnboundedReceiver<
-            std::sync::mpsc::Sender<Result<NetworkEndpoint, AcceptError>>,
-        >,
+        config: ClientConfig,
+        queue: QueueConfig,
+        mut receiver: tokio::sync::mpsc::UnboundedReceiver<ConnectRequest>,
     ) {
-        let client = Client::new().expect("unable to bind to port or find certificate");
-        debug!("started");
-
-        loop {
-            let Some(sender) = receiver.recv().await else {
-                panic!("connection closed");
+        let mut client = None;
+
+        while let Some(ConnectRequest { remote, sender }) = receiver.recv().await {
+            // created on demand so a failure to bind is reported and retried.
+            let client = match &client {
+                Some(client) => client,
+                None => match Client::new(config.clone()) {
+                    Ok(new_client) => {
+                        debug!("started");
+                        client.insert(new_client)
+                    }
+                    Err(error) => {
+                        let error = NetworkError::Bind {
+                            error,
+                            local_address: config.listen_address(),
+                        };
+                        error!(error =% error, "unable to start");
+                        let _ = sender.send(Err(error));
+                        continue;
+                    }
+                },
             };
 
-            let connecting = client.connect().unwrap();
+            let (remote_address, server_name) = remote
+                .unwrap_or_else(|| (config.remote_address(), config.server_name().to_string()));
+            let connecting = match client.connect_to(remote_address, &server_name) {
+                Ok(connecting) => connecting,
+                Err(error) => {
+                    let error = NetworkError::Connect {
+                        error,
+                        remote_address,
+                    };
+                    warn!(error =% error, "unable to connect");
+                    let _ = sender.send(Err(error));
+                    continue;
+                }
+            };
 
             tokio::spawn(async move {
                 let span = error_span!(
@@ -105,55 +255,107 @@ impl NetworkClient {
                 let _guard = span.enter();
                 debug!("connecting");
 
+                let remote_address = connecting.remote_address();
                 let connection = match connecting.accept().await {
                     Ok(connection) => connection,
                     Err(error) => {
-                        sender.send(Err(error)).unwrap();
+                        let error = NetworkError::Accept {
+                            error,
+                            remote_address,
+                        };
+                        warn!(error =% error, "unable to accept connection");
+                        let _ = sender.send(Err(error));
                         return;
                     }
                 };
 
-                let (from_bevy, to_tokio) = tokio::sync::mpsc::unbounded_channel();
-                let (from_tokio, to_bevy) = std::sync::mpsc::channel();
-
-                sender
-                    .send(Ok(NetworkEndpoint::new(&connection, from_bevy, to_bevy)))
-                    .unwrap();
+                let send_queue = Arc::new(SendQueue::new(queue));
+                let (from_tokio, to_bevy) = tokio::sync::mpsc::channel(queue.capacity().max(1));
+
+                if sender
+                    .send(Ok(NetworkEndpoint::new(
+                        &connection,
+                        send_queue.clone(),
+                        to_bevy,
+                    )))
+                    .is_err()
+                {
+                    // the actor who initiated the connect is no longer interested in the endpoint.
+                    debug!("failed to route endpoint");
+                    return;
+                }
 
-                NetworkEndpoint::bridge(connection, from_tokio, to_tokio).await;
+                NetworkEndpoint::bridge(connection, from_tokio, send_queue).await;
             });
         }
+
+        debug!("stopped");
     }
 }
 
+/// Connect Request.
+struct ConnectRequest {
+    /// Remote address and server name, those of the [`ClientConfig`] when
+    /// absent.
+    remote: Option<(SocketAddr, String)>,
+
+    /// Sender.
+    sender: chaos_symphony_async::Sender<Result<NetworkEndpoint, NetworkError>>,
+}
+
 /// Network Endpoint.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Component)]
 pub struct NetworkEndpoint {
+    connection: Connection,
+    encoding: Encoding,
     id: usize,
     is_disconnected: std::sync::atomic::AtomicBool,
-    receiver: Arc<std::sync::Mutex<std::sync::mpsc::Receiver<NetworkRecv>>>,
+    peer_certificate: Option<PeerCertificate>,
+    receiver: std::sync::Mutex<tokio::sync::mpsc::Receiver<NetworkRecv>>,
     remote_address: SocketAddr,
-    sender: tokio::sync::mpsc::UnboundedSender<NetworkSend>,
+    sender: Arc<SendQueue>,
+}
+
+impl Drop for NetworkEndpoint {
+    fn drop(&mut self) {
+        // messages already queued are still sent.
+        self.sender.close();
+    }
 }
 
 impl NetworkEndpoint {
     /// Creates a new [`NetworkEndpoint`].
     #[must_use]
-    pub fn new(
+    fn new(
         connection: &Connection,
-        sender: tokio::sync::mpsc::UnboundedSender<NetworkSend>,
-        receiver: std::sync::mpsc::Receiver<NetworkRecv>,
+        sender: Arc<SendQueue>,
+        receiver: tokio::sync::mpsc::Receiver<NetworkRecv>,
     ) -> Self {
         Self {
+            connection: connection.clone(),
+            encoding: connection.encoding(),
             id: connection.id(),
             is_disconnected: AtomicBool::new(false),
-            receiver: Arc::new(std::sync::Mutex::new(receiver)),
+            peer_certificate: connection.peer_certificate().cloned(),
+            receiver: std::sync::Mutex::new(receiver),
             remote_address: connection.remote_address(),
             sender,
         }
     }
 
+    /// Returns the close reason of this [`NetworkEndpoint`].
+    ///
+    /// Present once closed by either endpoint with a reason or timed out.
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        self.connection.close_reason()
+    }
+
+    /// Returns the encoding of this [`NetworkEndpoint`].
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
     /// Returns the id of this [`NetworkEndpoint`].
     pub fn id(&self) -> usize {
         self.id
@@ -164,11 +366,34 @@ impl NetworkEndpoint {
         self.is_disconnected.load(Ordering::Relaxed)
     }
 
+    /// Returns the peer certificate of this [`NetworkEndpoint`].
+    pub fn peer_certificate(&self) -> Option<&PeerCertificate> {
+        self.peer_certificate.as_ref()
+    }
+
+    /// Returns the queue depth of this [`NetworkEndpoint`].
+    ///
+    /// Number of messages waiting to be sent.
+    pub fn queue_depth(&self) -> usize {
+        self.sender.depth()
+    }
+
+    /// Returns the number of messages dropped by the send queue of this
+    /// [`NetworkEndpoint`].
+    pub fn queue_dropped(&self) -> u64 {
+        self.sender.dropped()
+    }
+
     /// Returns the remote address of this [`NetworkEndpoint`].
     pub fn remote_address(&self) -> SocketAddr {
         self.remote_address
     }
 
+    /// Returns the stats of this [`NetworkEndpoint`].
+    pub fn stats(&self) -> ConnectionStats {
+        self.connection.stats()
+    }
+
     /// Try receive message.
     ///
     /// # Errors
@@ -179,7 +404,17 @@ impl NetworkEndpoint {
     ///
     /// Will panic if [`Mutex`] is poisoned.
     pub fn try_recv(&self) -> Result<NetworkRecv, TryRecvError> {
-        let result = self.receiver.lock().expect("poisoned").try_recv();
+        let result =
+            self.receiver
+                .lock()
+                .expect("poisoned")
+                .try_recv()
+                .map_err(|error| match error {
+                    tokio::sync::mpsc::error::TryRecvError::Disconnected => {
+                        TryRecvError::Disconnected
+                    }
+                    tokio::sync::mpsc::error::TryRecvError::Empty => TryRecvError::Empty,
+                });
 
         if let Err(TryRecvError::Disconnected) = result {
             self.is_disconnected.store(true, Ordering::Relaxed);
@@ -188,38 +423,190 @@ impl NetworkEndpoint {
         result
     }
 
-    /// Try send blocking.
+    /// Try close.
+    ///
+    /// Closes the connection once the messages already sent are delivered.
     ///
     /// # Errors
     ///
     /// Will return `Err` if bevy-tokio bridge is disconnected.
+    pub fn try_close(
+        &self,
+        reason: CloseReason,
+        message: impl Into<String>,
+    ) -> Result<(), TrySendError> {
+        let result = self.sender.push(NetworkSend::Close {
+            message: message.into(),
+            reason,
+        });
+
+        if let Err(TrySendError::Disconnected) = result {
+            self.is_disconnected.store(true, Ordering::Relaxed);
+        }
+
+        result
+    }
+
+    /// Try send blocking.
+    ///
+    /// Sends on [`Channel::CONTROL`]. The returned [`Future`] times out when no
+    /// response is received within the timeout.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
     pub fn try_send_blocking(
         &self,
         message: Message,
-    ) -> Result<Future<Message>, tokio::sync::mpsc::error::SendError<NetworkSend>> {
-        let (sender, receiver) = std::sync::mpsc::channel();
+        timeout: Duration,
+    ) -> Result<Future<Message>, TrySendError> {
+        self.try_send_blocking_with_cancel(message, timeout, None)
+    }
+
+    /// Try send blocking with cancel message.
+    ///
+    /// Like [`NetworkEndpoint::try_send_blocking`]. When the returned
+    /// [`Future`] is dropped or times out before the response is received, the
+    /// pending request is forgotten and the cancel message, if any, is sent to
+    /// the peer.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    pub fn try_send_blocking_with_cancel(
+        &self,
+        message: Message,
+        timeout: Duration,
+        cancel: Option<Message>,
+    ) -> Result<Future<Message>, TrySendError> {
+        let (sender, receiver) = chaos_symphony_async::channel();
+        let future = Future::new(receiver).with_timeout(timeout);
+
+        let deadline = future.deadline().unwrap_or_else(Instant::now);
+        self.try_send_pending(message, deadline, None, sender, cancel)
+            .map(|cancel| future.with_cancel(cancel))
+    }
+
+    /// Try send streaming with cancel message.
+    ///
+    /// Like [`NetworkEndpoint::try_send_blocking_with_cancel`], but every
+    /// response is received until one on the end endpoint, after which the
+    /// returned [`Stream`] is disconnected. The timeout applies to the whole
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    pub fn try_send_streaming(
+        &self,
+        message: Message,
+        timeout: Duration,
+        end_endpoint: String,
+        cancel: Option<Message>,
+    ) -> Result<Stream<Message>, TrySendError> {
+        let (sender, receiver) = chaos_symphony_async::channel();
+        let stream = Stream::new(receiver).with_timeout(timeout);
+
+        let deadline = stream.deadline().unwrap_or_else(Instant::now);
+        self.try_send_pending(message, deadline, Some(end_endpoint), sender, cancel)
+            .map(|cancel| stream.with_cancel(cancel))
+    }
 
-        let result = self.sender.send(NetworkSend::Blocking { message, sender });
+    /// Queues a request awaiting responses.
+    ///
+    /// Returns the function cancelling it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    fn try_send_pending(
+        &self,
+        message: Message,
+        deadline: Instant,
+        end_endpoint: Option<String>,
+        sender: chaos_symphony_async::Sender<Message>,
+        cancel: Option<Message>,
+    ) -> Result<impl FnOnce() + Send + 'static, TrySendError> {
+        let id = message.id.clone();
+        let result = self.sender.push(NetworkSend::Blocking {
+            channel: Channel::CONTROL,
+            deadline,
+            end_endpoint,
+            message,
+            sender,
+        });
 
-        if result.is_err() {
+        if let Err(TrySendError::Disconnected) = result {
             self.is_disconnected.store(true, Ordering::Relaxed);
         }
 
-        result.map(|()| Future::new(receiver))
+        let queue = self.sender.clone();
+        result.map(|()| {
+            move || {
+                // fails once disconnected, when nothing is left to cancel.
+                let _ = queue.push(NetworkSend::Cancel {
+                    id,
+                    message: cancel,
+                });
+            }
+        })
     }
 
     /// Try send non blocking.
     ///
+    /// Sends on [`Channel::CONTROL`].
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if bevy-tokio bridge is disconnected.
-    pub fn try_send_non_blocking(
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    pub fn try_send_non_blocking(&self, message: Message) -> Result<(), TrySendError> {
+        self.try_send_non_blocking_with(Channel::CONTROL, Reliability::Reliable, message)
+    }
+
+    /// Try send non blocking with channel and reliability.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    pub fn try_send_non_blocking_with(
+        &self,
+        channel: Channel,
+        reliability: Reliability,
+        message: Message,
+    ) -> Result<(), TrySendError> {
+        self.try_send_non_blocking_coalesced(channel, reliability, None, message)
+    }
+
+    /// Try send non blocking with channel, reliability and coalesce key.
+    ///
+    /// Messages with the same endpoint and coalesce key supersede each other
+    /// under [`OverflowPolicy::Coalesce`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected or the send
+    /// queue is full.
+    pub fn try_send_non_blocking_coalesced(
         &self,
+        channel: Channel,
+        reliability: Reliability,
+        key: Option<String>,
         message: Message,
-    ) -> Result<(), tokio::sync::mpsc::error::SendError<NetworkSend>> {
-        let result = self.sender.send(NetworkSend::NonBlocking { message });
+    ) -> Result<(), TrySendError> {
+        let result = self.sender.push(NetworkSend::NonBlocking {
+            channel,
+            key,
+            message,
+            reliability,
+        });
 
-        if result.is_err() {
+        if let Err(TrySendError::Disconnected) = result {
             self.is_disconnected.store(true, Ordering::Relaxed);
         }
 
@@ -237,15 +624,14 @@ impl NetworkEndpoint {
     )]
     async fn bridge(
         connection: Connection,
-        sender: std::sync::mpsc::Sender<NetworkRecv>,
-        receiver: tokio::sync::mpsc::UnboundedReceiver<NetworkSend>,
+        sender: tokio::sync::mpsc::Sender<NetworkRecv>,
+        receiver: Arc<SendQueue>,
     ) {
         debug!("connected");
 
-        let database = Arc::new(tokio::sync::Mutex::new(HashMap::<
-            String,
-            std::sync::mpsc::Sender<Message>,
-        >::new()));
+        let database = Arc::new(tokio::sync::Mutex::new(
+            HashMap::<String, PendingRequest>::new(),
+        ));
 
         let (error_tx, mut error_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
 
@@ -255,12 +641,16 @@ impl NetworkEndpoint {
             connection.clone(),
             sender,
         );
-        let quit_outbound = Self::bridge_outbounds(error_tx, database, connection, receiver);
+        let quit_outbound =
+            Self::bridge_outbounds(error_tx, database.clone(), connection, receiver.clone());
+        let quit_sweep = Self::bridge_sweeps(database);
 
         error_rx.recv().await;
 
         drop(quit_inbound);
         drop(quit_outbound);
+        drop(quit_sweep);
+        receiver.close();
 
         debug!("disconnected");
     }
@@ -276,16 +666,17 @@ impl NetworkEndpoint {
     )]
     fn bridge_inbounds(
         error_tx: tokio::sync::mpsc::UnboundedSender<()>,
-        database: Arc<tokio::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<Message>>>>,
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
         connection: Connection,
-        sender: std::sync::mpsc::Sender<NetworkRecv>,
+        sender: tokio::sync::mpsc::Sender<NetworkRecv>,
     ) -> tokio::sync::mpsc::Sender<()> {
         let (quit_tx, mut quit_rx) = tokio::sync::mpsc::channel::<()>(1);
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     result = connection.recv() => {
-                        tokio::spawn(Self::bridge_inbound(error_tx.clone(), database.clone(), sender.clone(), result));
+                        // awaited in place to preserve message order.
+                        Self::bridge_inbound(error_tx.clone(), database.clone(), sender.clone(), result).await;
                     }
                     _ = quit_rx.recv() => {
                         debug!("quit received");
@@ -308,16 +699,60 @@ impl NetworkEndpoint {
     )]
     fn bridge_outbounds(
         error_tx: tokio::sync::mpsc::UnboundedSender<()>,
-        database: Arc<tokio::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<Message>>>>,
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
         connection: Connection,
-        mut receiver: tokio::sync::mpsc::UnboundedReceiver<NetworkSend>,
+        receiver: Arc<SendQueue>,
+    ) -> tokio::sync::mpsc::Sender<()> {
+        let (quit_tx, mut quit_rx) = tokio::sync::mpsc::channel::<()>(1);
+        tokio::spawn(async move {
+            // one send in flight per channel preserves message order within a
+            // channel, without a stalled channel blocking the others. sends
+            // are polled on this task rather than spawned per message.
+            let mut busy = HashSet::new();
+            let mut in_flight = FuturesUnordered::new();
+            loop {
+                tokio::select! {
+                    result = receiver.pop(&busy) => {
+                        let Some((channel, reliability, message)) = Self::bridge_outbound(error_tx.clone(), database.clone(), connection.clone(), result).await else {
+                            continue;
+                        };
+                        busy.insert(channel);
+                        in_flight.push(Self::bridge_send(error_tx.clone(), database.clone(), connection.clone(), channel, reliability, message));
+                    }
+                    Some(channel) = in_flight.next(), if !in_flight.is_empty() => {
+                        busy.remove(&channel);
+                    }
+                    _ = quit_rx.recv() => {
+                        debug!("quit received");
+                        return;
+                    }
+                }
+            }
+        });
+        quit_tx
+    }
+
+    /// Removes pending requests past their deadline.
+    ///
+    /// Their [`Future`] times out on its own, removing them releases the
+    /// sender.
+    fn bridge_sweeps(
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
     ) -> tokio::sync::mpsc::Sender<()> {
         let (quit_tx, mut quit_rx) = tokio::sync::mpsc::channel::<()>(1);
         tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
             loop {
                 tokio::select! {
-                    result = receiver.recv() => {
-                        tokio::spawn(Self::bridge_outbound(error_tx.clone(), database.clone(), connection.clone(), result));
+                    _ = interval.tick() => {
+                        let now = Instant::now();
+                        database.lock().await.retain(|id, pending| {
+                            let is_pending = pending.deadline > now;
+                            if !is_pending {
+                                debug!(message_id = id, "pending request timed out");
+                            }
+                            is_pending
+                        });
                     }
                     _ = quit_rx.recv() => {
                         debug!("quit received");
@@ -336,14 +771,14 @@ impl NetworkEndpoint {
     )]
     async fn bridge_inbound(
         error_tx: tokio::sync::mpsc::UnboundedSender<()>,
-        database: Arc<tokio::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<Message>>>>,
-        sender: std::sync::mpsc::Sender<NetworkRecv>,
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
+        sender: tokio::sync::mpsc::Sender<NetworkRecv>,
         result: Result<Message, RecvError>,
     ) {
         let message = match result {
             Ok(message) => message,
             Err(error) => {
-                warn!(error =? error, "bridge error");
+                warn!(error =% error, "bridge error");
                 if error_tx.send(()).is_err() {
                     warn!("failed to communicate error");
                 }
@@ -351,15 +786,42 @@ impl NetworkEndpoint {
             }
         };
 
-        if let Some(sender) = database.lock().await.remove(&message.id) {
+        let mut pending_requests = database.lock().await;
+        if let Some(pending) = pending_requests.get(&message.id) {
+            // streaming requests stay pending until their end message.
+            let is_end = pending
+                .end_endpoint
+                .as_ref()
+                .is_none_or(|end_endpoint| *end_endpoint == message.endpoint);
+            let sender = if is_end {
+                pending_requests
+                    .remove(&message.id)
+                    .and_then(|pending| pending.sender)
+            } else {
+                pending.sender.clone()
+            };
+            drop(pending_requests);
+
+            let Some(sender) = sender else {
+                debug!(
+                    message_id = message.id,
+                    "discarded response to cancelled request"
+                );
+                return;
+            };
             if sender.send(message).is_err() {
                 // the actor who sent the blocking request is no longer interested in the response.
                 warn!("failed to route message to blocking channel");
             }
             return;
         }
+        drop(pending_requests);
 
-        if sender.send(NetworkRecv::NonBlocking { message }).is_err() {
+        if sender
+            .send(NetworkRecv::NonBlocking { message })
+            .await
+            .is_err()
+        {
             warn!("failed to route message to non-blocking channel");
             if error_tx.send(()).is_err() {
                 warn!("failed to communicate error");
@@ -368,6 +830,8 @@ impl NetworkEndpoint {
     }
 
     /// Bridges outbound bevy-tokio runtime using channels.
+    ///
+    /// Returns the message to send, if any.
     #[instrument(
         name = "network_endpoint_bridge_outbound",
         skip(error_tx, database, connection, result),
@@ -378,40 +842,224 @@ impl NetworkEndpoint {
     )]
     async fn bridge_outbound(
         error_tx: tokio::sync::mpsc::UnboundedSender<()>,
-        database: Arc<tokio::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<Message>>>>,
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
         connection: Connection,
         result: Option<NetworkSend>,
-    ) {
+    ) -> Option<(Channel, Reliability, Message)> {
         let Some(network_send) = result else {
             warn!("bridge error");
             if error_tx.send(()).is_err() {
                 warn!("failed to communicate error");
             }
-            return;
+            return None;
         };
 
-        let (message, blocking) = match network_send {
-            NetworkSend::Blocking { message, sender } => {
-                let id = message.id.clone();
-                (message, Some((id, sender)))
+        let (channel, message, reliability) = match network_send {
+            NetworkSend::Cancel { id, message } => {
+                // kept until its deadline to discard a late response.
+                let is_pending = database
+                    .lock()
+                    .await
+                    .get_mut(&id)
+                    .and_then(|pending| pending.sender.take())
+                    .is_some();
+                debug!(message_id = id, is_pending, "cancelled");
+                match message {
+                    Some(message) if is_pending => {
+                        (Channel::CONTROL, message, Reliability::Reliable)
+                    }
+                    _ => return None,
+                }
+            }
+            NetworkSend::Close { message, reason } => {
+                debug!(reason =? reason, message, "closing");
+                connection.close(reason, &message).await;
+                return None;
             }
-            NetworkSend::NonBlocking { message } => (message, None),
+            NetworkSend::Blocking {
+                channel,
+                deadline,
+                end_endpoint,
+                message,
+                sender,
+            } => {
+                // registered before sending as the response may arrive before the send completes.
+                database.lock().await.insert(
+                    message.id.clone(),
+                    PendingRequest {
+                        deadline,
+                        end_endpoint,
+                        sender: Some(sender),
+                    },
+                );
+                (channel, message, Reliability::Reliable)
+            }
+            NetworkSend::NonBlocking {
+                channel,
+                message,
+                reliability,
+                ..
+            } => (channel, message, reliability),
         };
 
-        if connection.send(message).await.is_err() {
-            warn!("failed to route message to connection");
+        Some((channel, reliability, message))
+    }
+
+    /// Sends a message on the connection.
+    ///
+    /// Returns the channel once sent.
+    #[instrument(
+        name = "network_endpoint_bridge_send",
+        skip(error_tx, database, connection, message),
+        fields(
+            id = connection.id(),
+            remote_address =% connection.remote_address()
+        )
+    )]
+    async fn bridge_send(
+        error_tx: tokio::sync::mpsc::UnboundedSender<()>,
+        database: Arc<tokio::sync::Mutex<HashMap<String, PendingRequest>>>,
+        connection: Connection,
+        channel: Channel,
+        reliability: Reliability,
+        message: Message,
+    ) -> Channel {
+        let id = message.id.clone();
+        if let Err(error) = connection.send_with(channel, reliability, message).await {
+            warn!(error =% error, "failed to route message to connection");
+            database.lock().await.remove(&id);
             if error_tx.send(()).is_err() {
                 warn!("failed to communicate error");
             }
-            return;
         }
+        channel
+    }
+}
+
+/// Network Endpoint Stats.
+///
+/// Refreshed every frame on entities with a [`NetworkEndpoint`].
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Component)]
+pub struct NetworkEndpointStats {
+    /// Inner.
+    pub inner: ConnectionStats,
+
+    /// Queue Depth.
+    pub queue_depth: usize,
+
+    /// Queue Dropped.
+    pub queue_dropped: u64,
+}
+
+/// Refreshes the [`NetworkEndpointStats`] of each [`NetworkEndpoint`].
+fn refresh_stats(
+    mut commands: Commands,
+    mut endpoints: Query<(Entity, &NetworkEndpoint, Option<&mut NetworkEndpointStats>)>,
+) {
+    endpoints
+        .iter_mut()
+        .for_each(|(entity, endpoint, stats)| match stats {
+            Some(mut stats) => {
+                stats.inner = endpoint.stats();
+                stats.queue_depth = endpoint.queue_depth();
+                stats.queue_dropped = endpoint.queue_dropped();
+            }
+            None => {
+                commands.entity(entity).insert(NetworkEndpointStats {
+                    inner: endpoint.stats(),
+                    queue_depth: endpoint.queue_depth(),
+                    queue_dropped: endpoint.queue_dropped(),
+                });
+            }
+        });
+}
+
+/// Network Error.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug)]
+pub enum NetworkError {
+    /// Accept.
+    Accept {
+        /// Error.
+        error: AcceptError,
+
+        /// Remote Address.
+        remote_address: SocketAddr,
+    },
+
+    /// Bind.
+    ///
+    /// Unable to bind to port or find certificate.
+    Bind {
+        /// Error.
+        error: io::Error,
+
+        /// Local Address.
+        local_address: SocketAddr,
+    },
+
+    /// Connect.
+    Connect {
+        /// Error.
+        error: ConnectError,
+
+        /// Remote Address.
+        remote_address: SocketAddr,
+    },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accept { remote_address, .. } => {
+                write!(f, "unable to accept connection from {remote_address}")
+            }
+            Self::Bind { local_address, .. } => {
+                write!(f, "unable to bind to {local_address} or find certificate")
+            }
+            Self::Connect { remote_address, .. } => {
+                write!(f, "unable to connect to {remote_address}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Accept { error, .. } => Some(error),
+            Self::Bind { error, .. } => Some(error),
+            Self::Connect { error, .. } => Some(error),
+        }
+    }
+}
 
-        if let Some((id, sender)) = blocking {
-            database.lock().await.insert(id, sender);
+/// Try Send Error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError {
+    /// Disconnected.
+    ///
+    /// The bevy-tokio bridge is disconnected or the send queue is closed.
+    Disconnected,
+
+    /// Full.
+    ///
+    /// The send queue is full and the message cannot be coalesced.
+    Full,
+}
+
+impl fmt::Display for TrySendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => write!(f, "bevy-tokio bridge disconnected"),
+            Self::Full => write!(f, "send queue full"),
         }
     }
 }
 
+impl std::error::Error for TrySendError {}
+
 /// Network Recv.
 #[allow(clippy::module_name_repetitions)]
 pub enum NetworkRecv {
@@ -422,42 +1070,162 @@ pub enum NetworkRecv {
     },
 }
 
+/// Pending Request.
+struct PendingRequest {
+    deadline: Instant,
+
+    /// Present for streaming requests, which end with a response on it.
+    end_endpoint: Option<String>,
+
+    /// Absent once cancelled, so a late response is discarded.
+    sender: Option<chaos_symphony_async::Sender<Message>>,
+}
+
 /// Network Send.
 #[allow(clippy::module_name_repetitions)]
 pub enum NetworkSend {
+    /// Cancel.
+    ///
+    /// Forgets a pending blocking request.
+    Cancel {
+        /// Id.
+        id: String,
+
+        /// Message.
+        ///
+        /// Sent to the peer if the request is still pending.
+        message: Option<Message>,
+    },
+
+    /// Close.
+    Close {
+        /// Message.
+        message: String,
+
+        /// Reason.
+        reason: CloseReason,
+    },
+
     /// Blocking.
     Blocking {
+        /// Channel.
+        channel: Channel,
+
+        /// Deadline.
+        ///
+        /// The pending request is discarded once passed.
+        deadline: Instant,
+
+        /// End Endpoint.
+        ///
+        /// Streams responses until one on this endpoint when present.
+        end_endpoint: Option<String>,
+
         /// Message.
         message: Message,
 
         /// Sender.
-        sender: std::sync::mpsc::Sender<Message>,
+        sender: chaos_symphony_async::Sender<Message>,
     },
 
     /// Non Blocking.
     NonBlocking {
+        /// Channel.
+        channel: Channel,
+
+        /// Key.
+        ///
+        /// Coalesces queued messages with the same endpoint and key.
+        key: Option<String>,
+
         /// Message.
         message: Message,
+
+        /// Reliability.
+        reliability: Reliability,
     },
 }
 
+impl NetworkSend {
+    /// Returns the channel of this [`NetworkSend`].
+    ///
+    /// Absent for close messages, which apply to every channel.
+    pub(crate) fn channel(&self) -> Option<Channel> {
+        match self {
+            Self::Cancel { .. } => Some(Channel::CONTROL),
+            Self::Close { .. } => None,
+            Self::Blocking { channel, .. } | Self::NonBlocking { channel, .. } => Some(*channel),
+        }
+    }
+
+    /// Is control.
+    ///
+    /// Whether this cancels or closes rather than sends a message.
+    pub(crate) fn is_control(&self) -> bool {
+        matches!(self, Self::Cancel { .. } | Self::Close { .. })
+    }
+
+    /// Is coalescable.
+    ///
+    /// Whether this may be superseded by a later message, as a non blocking
+    /// message with a coalesce key.
+    pub(crate) fn is_coalescable(&self) -> bool {
+        matches!(self, Self::NonBlocking { key: Some(_), .. })
+    }
+
+    /// Is coalesced by.
+    ///
+    /// Whether the other message supersedes this message.
+    pub(crate) fn is_coalesced_by(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::NonBlocking {
+                    key: Some(key),
+                    message,
+                    ..
+                },
+                Self::NonBlocking {
+                    key: Some(other_key),
+                    message: other_message,
+                    ..
+                },
+            ) => key == other_key && message.endpoint == other_message.endpoint,
+            _ => false,
+        }
+    }
+}
+
 /// Network Server.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Resource)]
 pub struct NetworkServer {
-    receiver: std::sync::Mutex<std::sync::mpsc::Receiver<NetworkEndpoint>>,
+    client_authentication: ClientAuthentication,
+    receiver: std::sync::Mutex<std::sync::mpsc::Receiver<Result<NetworkEndpoint, NetworkError>>>,
 }
 
 impl NetworkServer {
     /// Creates a new [`NetworkServer`].
-    fn new(receiver: std::sync::mpsc::Receiver<NetworkEndpoint>) -> Self {
+    fn new(
+        client_authentication: ClientAuthentication,
+        receiver: std::sync::mpsc::Receiver<Result<NetworkEndpoint, NetworkError>>,
+    ) -> Self {
         Self {
+            client_authentication,
             receiver: std::sync::Mutex::new(receiver),
         }
     }
 
+    /// Returns the client authentication of this [`NetworkServer`].
+    #[must_use]
+    pub fn client_authentication(&self) -> &ClientAuthentication {
+        &self.client_authentication
+    }
+
     /// Try to receive a new [`NetworkEndpoint`].
     ///
+    /// Failures to start the server or accept a connection are received as
+    /// [`NetworkError`].
+    ///
     /// # Errors
     ///
     /// Will return `Err` if bevy-tokio bridge is disconnected or empty.
@@ -465,21 +1233,32 @@ impl NetworkServer {
     /// # Panics
     ///
     /// Will panic if [`Mutex`] is poisoned.
-    pub fn try_recv(&self) -> Result<NetworkEndpoint, TryRecvError> {
+    pub fn try_recv(&self) -> Result<Result<NetworkEndpoint, NetworkError>, TryRecvError> {
         self.receiver.lock().expect("poisoned").try_recv()
     }
 
     /// Bridges bevy-tokio runtime using channels.
-    #[instrument(name = "network_server", skip(sender))]
-    async fn bridge(sender: std::sync::mpsc::Sender<NetworkEndpoint>) {
-        let server = Server::new().expect("unable to bind to port or find certificate");
+    #[instrument(name = "network_server", skip(config, queue, sender))]
+    async fn bridge(
+        config: ServerConfig,
+        queue: QueueConfig,
+        sender: std::sync::mpsc::Sender<Result<NetworkEndpoint, NetworkError>>,
+    ) {
+        let server = match Server::new(&config) {
+            Ok(server) => server,
+            Err(error) => {
+                let error = NetworkError::Bind {
+                    error,
+                    local_address: config.listen_address(),
+                };
+                error!(error =% error, "unable to start");
+                let _ = sender.send(Err(error));
+                return;
+            }
+        };
         debug!("started");
 
-        loop {
-            let Some(connecting) = server.accept().await else {
-                panic!("connection closed");
-            };
-
+        while let Some(connecting) = server.accept().await {
             let sender = sender.clone();
 
             tokio::spawn(async move {
@@ -489,25 +1268,47 @@ impl NetworkServer {
                 let _guard = span.enter();
                 debug!("connecting");
 
-                let connection = connecting.accept().await.unwrap();
-
-                let (from_bevy, to_tokio) = tokio::sync::mpsc::unbounded_channel();
-                let (from_tokio, to_bevy) = std::sync::mpsc::channel();
+                let remote_address = connecting.remote_address();
+                let connection = match connecting.accept().await {
+                    Ok(connection) => connection,
+                    Err(error) => {
+                        let error = NetworkError::Accept {
+                            error,
+                            remote_address,
+                        };
+                        warn!(error =% error, "unable to accept connection");
+                        let _ = sender.send(Err(error));
+                        return;
+                    }
+                };
 
-                sender
-                    .send(NetworkEndpoint::new(&connection, from_bevy, to_bevy))
-                    .unwrap();
+                let send_queue = Arc::new(SendQueue::new(queue));
+                let (from_tokio, to_bevy) = tokio::sync::mpsc::channel(queue.capacity().max(1));
+
+                if sender
+                    .send(Ok(NetworkEndpoint::new(
+                        &connection,
+                        send_queue.clone(),
+                        to_bevy,
+                    )))
+                    .is_err()
+                {
+                    debug!("failed to route endpoint");
+                    return;
+                }
 
-                NetworkEndpoint::bridge(connection, from_tokio, to_tokio).await;
+                NetworkEndpoint::bridge(connection, from_tokio, send_queue).await;
             });
         }
+
+        debug!("stopped");
     }
 }
 
 /// Connecting.
 #[derive(Component)]
 pub struct Connecting {
-    inner: Future<Result<NetworkEndpoint, AcceptError>>,
+    inner: Future<Result<NetworkEndpoint, NetworkError>>,
 }
 
 impl Connecting {
@@ -522,7 +1323,216 @@ impl Connecting {
     /// # Panics
     ///
     /// Will panic if [`Mutex`] is poisoned.
-    pub fn try_poll(&self) -> Poll<Result<Result<NetworkEndpoint, AcceptError>, PollError>> {
+    pub fn try_poll(&self) -> Poll<Result<Result<NetworkEndpoint, NetworkError>, PollError>> {
         self.inner.try_poll()
     }
 }
+
+impl std::future::Future for Connecting {
+    type Output = Result<Result<NetworkEndpoint, NetworkError>, PollError>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        std::pin::Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc::TryRecvError, time::Duration};
+
+    use bevy::{
+        prelude::*,
+        time::{TimePlugin, TimeUpdateStrategy},
+    };
+    use chaos_symphony_async::Poll;
+    use chaos_symphony_network::{
+        ClientConfig, ConnectError, MemoryNetwork, Message, Server, ServerConfig, TransportConfig,
+    };
+
+    use crate::{
+        testing, NetworkClient, NetworkClock, NetworkEndpoint, NetworkEndpointStats, NetworkError,
+        NetworkExecutor, NetworkPlugin, NetworkRecv, NetworkRuntime, NetworkServer,
+    };
+
+    #[test]
+    fn test_plugin_with_memory_transport() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let address = "[::1]:4433".parse().unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(NetworkPlugin {
+            client: Some(
+                ClientConfig::default()
+                    .with_remote_address(address)
+                    .with_transport(TransportConfig::Memory(network.clone())),
+            ),
+            queue: default(),
+            runtime: NetworkRuntime::CurrentThread,
+            server: Some(
+                ServerConfig::default()
+                    .with_listen_address(address)
+                    .with_transport(TransportConfig::Memory(network)),
+            ),
+        });
+
+        let mut connecting = app.world.resource::<NetworkClient>().connect().unwrap();
+        let client_endpoint = testing::until(|| {
+            app.update();
+            match connecting.try_poll() {
+                Poll::Ready(Ok(Ok(endpoint))) => Some(endpoint),
+                // the server bridge may not be listening yet.
+                Poll::Ready(Ok(Err(NetworkError::Connect {
+                    error: ConnectError::Refused,
+                    ..
+                }))) => {
+                    connecting = app.world.resource::<NetworkClient>().connect().unwrap();
+                    None
+                }
+                Poll::Ready(Ok(Err(error))) => panic!("{error}"),
+                Poll::Ready(Err(error)) => panic!("{error}"),
+                Poll::Pending => None,
+            }
+        });
+        let server_endpoint = match testing::until(|| {
+            app.update();
+            app.world.resource::<NetworkServer>().try_recv().ok()
+        }) {
+            Ok(endpoint) => endpoint,
+            Err(error) => panic!("{error}"),
+        };
+        let server_entity = app.world.spawn(server_endpoint).id();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/event/test".to_string(),
+            header: b"header".to_vec(),
+            payload: b"payload".to_vec(),
+        };
+
+        // Act
+        client_endpoint
+            .try_send_non_blocking(message.clone())
+            .unwrap();
+        let received = testing::until(|| {
+            app.update();
+            app.world
+                .get::<NetworkEndpoint>(server_entity)
+                .unwrap()
+                .try_recv()
+                .ok()
+        });
+
+        // Assert
+        let NetworkRecv::NonBlocking { message: received } = received;
+        assert_eq!(received, message);
+        assert!(app
+            .world
+            .get::<NetworkEndpointStats>(server_entity)
+            .is_some());
+    }
+
+    #[test]
+    fn test_clock_follows_time() {
+        // Arrange
+        let mut app = App::new();
+        app.add_plugins((
+            TimePlugin,
+            NetworkPlugin {
+                client: None,
+                queue: default(),
+                runtime: NetworkRuntime::CurrentThread,
+                server: None,
+            },
+        ))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+
+        // Act
+        (0..3).for_each(|_| app.update());
+
+        // Assert
+        let elapsed = app.world.resource::<Time>().elapsed();
+        assert_eq!(
+            app.world.resource::<NetworkClock>().inner.elapsed(),
+            elapsed
+        );
+        assert!(elapsed > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_plugin_without_runtime() {
+        // Arrange
+        let mut app = App::new();
+
+        // Act
+        app.add_plugins(NetworkPlugin {
+            client: Some(ClientConfig::default()),
+            queue: default(),
+            runtime: NetworkRuntime::CurrentThread,
+            server: None,
+        });
+        app.update();
+
+        // Assert
+        assert!(app.world.contains_resource::<NetworkClient>());
+        assert!(app.world.contains_resource::<NetworkExecutor>());
+    }
+
+    #[test]
+    fn test_network_error() {
+        // Arrange
+        let error = NetworkError::Connect {
+            error: ConnectError::Refused,
+            remote_address: "[::1]:4433".parse().unwrap(),
+        };
+
+        // Act
+        let source = std::error::Error::source(&error).map(ToString::to_string);
+
+        // Assert
+        assert_eq!(error.to_string(), "unable to connect to [::1]:4433");
+        assert_eq!(source.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn test_server_bind_failure() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let config = ServerConfig::default()
+            .with_listen_address("[::1]:4433".parse().unwrap())
+            .with_transport(TransportConfig::Memory(network));
+        let _server = Server::new(&config).unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(NetworkPlugin {
+            client: None,
+            queue: default(),
+            runtime: NetworkRuntime::CurrentThread,
+            server: Some(config),
+        });
+
+        // Act
+        let result = testing::until(|| {
+            app.update();
+            app.world.resource::<NetworkServer>().try_recv().ok()
+        });
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(NetworkError::Bind { local_address, .. }) if local_address.port() == 4433
+        ));
+        assert!(matches!(
+            testing::until(|| {
+                app.update();
+                app.world.resource::<NetworkServer>().try_recv().err()
+            }),
+            TryRecvError::Disconnected
+        ));
+    }
+}
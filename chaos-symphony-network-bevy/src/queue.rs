@@ -0,0 +1,437 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use chaos_symphony_network::Channel;
+
+use crate::{NetworkSend, TrySendError};
+
+/// Overflow Policy.
+///
+/// Applied when sending to a full send queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Coalesce.
+    ///
+    /// Replaces the queued message with the same endpoint and coalesce key,
+    /// dropping the oldest message with a coalesce key otherwise. Fails when
+    /// no queued message has a coalesce key.
+    Coalesce,
+
+    /// Disconnect.
+    ///
+    /// Discards the queue and disconnects the endpoint.
+    #[default]
+    Disconnect,
+
+    /// Drop Newest.
+    DropNewest,
+
+    /// Drop Oldest.
+    ///
+    /// Drops the newest message instead when only cancel and close messages
+    /// are queued. Requests dropped this way are received as disconnected.
+    DropOldest,
+}
+
+/// Queue Config.
+#[derive(Debug, Clone, Copy)]
+pub struct QueueConfig {
+    capacity: usize,
+    overflow: OverflowPolicy,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+}
+
+impl QueueConfig {
+    /// Returns the capacity of this [`QueueConfig`].
+    ///
+    /// Maximum number of messages queued per endpoint and direction.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the overflow policy of this [`QueueConfig`].
+    ///
+    /// Received messages are not dropped, the connection is read no faster
+    /// than they are consumed instead.
+    #[must_use]
+    pub fn overflow(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// With capacity.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// With overflow policy.
+    #[must_use]
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// Send Queue.
+///
+/// Bounded queue of messages sent from bevy to the tokio bridge.
+pub(crate) struct SendQueue {
+    config: QueueConfig,
+    dropped: AtomicU64,
+    notify: tokio::sync::Notify,
+    state: Mutex<SendQueueState>,
+}
+
+/// Send Queue State.
+#[derive(Default)]
+struct SendQueueState {
+    is_closed: bool,
+    queue: VecDeque<NetworkSend>,
+}
+
+impl SendQueue {
+    /// Creates a new [`SendQueue`].
+    pub(crate) fn new(config: QueueConfig) -> Self {
+        Self {
+            config,
+            dropped: AtomicU64::new(0),
+            notify: tokio::sync::Notify::new(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Closes the queue.
+    ///
+    /// Messages already queued are still popped.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn close(&self) {
+        self.state.lock().expect("poisoned").is_closed = true;
+        self.notify.notify_one();
+    }
+
+    /// Returns the depth of this [`SendQueue`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn depth(&self) -> usize {
+        self.state.lock().expect("poisoned").queue.len()
+    }
+
+    /// Returns the number of messages dropped by this [`SendQueue`].
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Pops the oldest message on a channel that is not busy, waiting until
+    /// one is queued.
+    ///
+    /// Close messages wait until every earlier message is popped and no
+    /// channel is busy, later messages wait for them.
+    ///
+    /// Returns `None` once closed and empty.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) async fn pop(&self, busy: &HashSet<Channel>) -> Option<NetworkSend> {
+        loop {
+            {
+                let mut state = self.state.lock().expect("poisoned");
+                let mut index = None;
+                for (i, queued) in state.queue.iter().enumerate() {
+                    match queued.channel() {
+                        Some(channel) if busy.contains(&channel) => {}
+                        Some(_) => {
+                            index = Some(i);
+                            break;
+                        }
+                        None => {
+                            if i == 0 && busy.is_empty() {
+                                index = Some(i);
+                            }
+                            break;
+                        }
+                    }
+                }
+                if let Some(network_send) = index.and_then(|index| state.queue.remove(index)) {
+                    return Some(network_send);
+                }
+                if state.is_closed && state.queue.is_empty() {
+                    return None;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Pushes a message, applying the overflow policy when full.
+    ///
+    /// Cancel and close messages are never dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if closed, disconnected by the overflow policy or
+    /// full with nothing to coalesce.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn push(&self, network_send: NetworkSend) -> Result<(), TrySendError> {
+        let mut state = self.state.lock().expect("poisoned");
+        if state.is_closed {
+            return Err(TrySendError::Disconnected);
+        }
+
+        if state.queue.len() >= self.config.capacity && !network_send.is_control() {
+            match self.config.overflow {
+                OverflowPolicy::Coalesce => {
+                    if let Some(queued) = state
+                        .queue
+                        .iter_mut()
+                        .find(|queued| queued.is_coalesced_by(&network_send))
+                    {
+                        *queued = network_send;
+                        return Ok(());
+                    }
+
+                    let Some(index) = state.queue.iter().position(NetworkSend::is_coalescable)
+                    else {
+                        return Err(TrySendError::Full);
+                    };
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    state.queue.remove(index);
+                }
+                OverflowPolicy::Disconnect => {
+                    state.is_closed = true;
+                    state.queue.clear();
+                    drop(state);
+                    self.notify.notify_one();
+                    return Err(TrySendError::Disconnected);
+                }
+                OverflowPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                OverflowPolicy::DropOldest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    let Some(index) = state.queue.iter().position(|queued| !queued.is_control())
+                    else {
+                        return Ok(());
+                    };
+                    state.queue.remove(index);
+                }
+            }
+        }
+
+        state.queue.push_back(network_send);
+        drop(state);
+        self.notify.notify_one();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Instant};
+
+    use chaos_symphony_network::{Channel, CloseReason, Message, Reliability};
+
+    use crate::{queue::SendQueue, NetworkSend, OverflowPolicy, QueueConfig, TrySendError};
+
+    /// Creates a message with the id.
+    fn message(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            endpoint: "/event/test".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        }
+    }
+
+    /// Creates a blocking message.
+    fn blocking(id: &str) -> NetworkSend {
+        NetworkSend::Blocking {
+            channel: Channel::CONTROL,
+            deadline: Instant::now(),
+            end_endpoint: None,
+            message: message(id),
+            sender: chaos_symphony_async::channel().0,
+        }
+    }
+
+    /// Creates a non blocking message on the channel.
+    fn non_blocking(channel: Channel, id: &str) -> NetworkSend {
+        NetworkSend::NonBlocking {
+            channel,
+            key: None,
+            message: message(id),
+            reliability: Reliability::Reliable,
+        }
+    }
+
+    /// Creates a non blocking message with the coalesce key.
+    fn keyed(key: &str, id: &str) -> NetworkSend {
+        NetworkSend::NonBlocking {
+            channel: Channel::CONTROL,
+            key: Some(key.to_string()),
+            message: message(id),
+            reliability: Reliability::Unreliable,
+        }
+    }
+
+    /// Returns the message id of the network send.
+    fn id(network_send: &NetworkSend) -> Option<&str> {
+        match network_send {
+            NetworkSend::Blocking { message, .. } | NetworkSend::NonBlocking { message, .. } => {
+                Some(&message.id)
+            }
+            NetworkSend::Cancel { .. } | NetworkSend::Close { .. } => None,
+        }
+    }
+
+    /// Closes the queue, returning the ids of the messages left.
+    async fn drain(queue: &SendQueue) -> Vec<String> {
+        queue.close();
+        let mut ids = Vec::new();
+        while let Some(network_send) = queue.pop(&HashSet::new()).await {
+            ids.extend(id(&network_send).map(ToString::to_string));
+        }
+        ids
+    }
+
+    /// Creates a queue with the capacity and overflow policy.
+    fn queue(capacity: usize, overflow: OverflowPolicy) -> SendQueue {
+        SendQueue::new(
+            QueueConfig::default()
+                .with_capacity(capacity)
+                .with_overflow(overflow),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_overflow_coalesce() {
+        // Arrange
+        let queue = queue(2, OverflowPolicy::Coalesce);
+        queue.push(keyed("a", "1")).unwrap();
+        queue.push(non_blocking(Channel::CONTROL, "2")).unwrap();
+
+        // Act
+        let replaced = queue.push(keyed("a", "3"));
+        let replaced_dropped = queue.dropped();
+        let evicted = queue.push(blocking("4"));
+        let full = queue.push(blocking("5"));
+
+        // Assert
+        assert!(replaced.is_ok());
+        assert_eq!(replaced_dropped, 0);
+        assert!(evicted.is_ok());
+        assert!(matches!(full, Err(TrySendError::Full)));
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(drain(&queue).await, ["2", "4"]);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_disconnect() {
+        // Arrange
+        let queue = queue(1, OverflowPolicy::Disconnect);
+        queue.push(non_blocking(Channel::CONTROL, "1")).unwrap();
+
+        // Act
+        let overflow = queue.push(non_blocking(Channel::CONTROL, "2"));
+
+        // Assert
+        assert!(matches!(overflow, Err(TrySendError::Disconnected)));
+        assert!(matches!(
+            queue.push(non_blocking(Channel::CONTROL, "3")),
+            Err(TrySendError::Disconnected)
+        ));
+        assert!(drain(&queue).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drop_newest() {
+        // Arrange
+        let queue = queue(1, OverflowPolicy::DropNewest);
+        queue.push(non_blocking(Channel::CONTROL, "1")).unwrap();
+
+        // Act
+        let overflow = queue.push(non_blocking(Channel::CONTROL, "2"));
+
+        // Assert
+        assert!(overflow.is_ok());
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(drain(&queue).await, ["1"]);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drop_oldest() {
+        // Arrange
+        let queue = queue(1, OverflowPolicy::DropOldest);
+        queue.push(non_blocking(Channel::CONTROL, "1")).unwrap();
+
+        // Act
+        let overflow = queue.push(non_blocking(Channel::CONTROL, "2"));
+
+        // Assert
+        assert!(overflow.is_ok());
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(drain(&queue).await, ["2"]);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drop_oldest_keeps_control() {
+        // Arrange
+        let queue = queue(1, OverflowPolicy::DropOldest);
+        queue
+            .push(NetworkSend::Close {
+                message: String::new(),
+                reason: CloseReason::Kicked,
+            })
+            .unwrap();
+
+        // Act
+        let overflow = queue.push(non_blocking(Channel::CONTROL, "1"));
+
+        // Assert
+        assert!(overflow.is_ok());
+        assert_eq!(queue.depth(), 1);
+        assert_eq!(queue.dropped(), 1);
+        assert!(drain(&queue).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pop_skips_busy_channel() {
+        // Arrange
+        let queue = SendQueue::new(QueueConfig::default());
+        queue.push(non_blocking(Channel::REPLICATION, "1")).unwrap();
+        queue.push(non_blocking(Channel::REPLICATION, "2")).unwrap();
+        queue.push(non_blocking(Channel::CONTROL, "3")).unwrap();
+        let busy = HashSet::from([Channel::REPLICATION]);
+
+        // Act
+        let popped = queue.pop(&busy).await.unwrap();
+
+        // Assert
+        assert_eq!(id(&popped), Some("3"));
+        assert_eq!(drain(&queue).await, ["1", "2"]);
+    }
+}
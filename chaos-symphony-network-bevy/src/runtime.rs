@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+/// Network Runtime.
+///
+/// Tokio runtime the bevy-tokio bridge is spawned on.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub enum NetworkRuntime {
+    /// Current Thread.
+    ///
+    /// Owned current thread runtime, driven by a dedicated thread.
+    CurrentThread,
+
+    /// Handle.
+    ///
+    /// Runtime owned by the caller.
+    Handle(tokio::runtime::Handle),
+
+    /// Multi Thread.
+    ///
+    /// Owned multi thread runtime.
+    MultiThread {
+        /// Worker Threads.
+        ///
+        /// Defaults to the number of cores when absent.
+        worker_threads: Option<usize>,
+    },
+}
+
+impl Default for NetworkRuntime {
+    fn default() -> Self {
+        Self::MultiThread {
+            worker_threads: None,
+        }
+    }
+}
+
+impl NetworkRuntime {
+    /// Starts the [`NetworkExecutor`] described by this [`NetworkRuntime`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the runtime or its thread cannot be created.
+    pub(crate) fn start(&self) -> NetworkExecutor {
+        match self {
+            Self::CurrentThread => {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build tokio runtime");
+                let handle = runtime.handle().clone();
+
+                let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+                std::thread::Builder::new()
+                    .name("network".to_string())
+                    .spawn(move || {
+                        // resolves once the executor is dropped.
+                        let _ = runtime.block_on(shutdown_rx);
+                    })
+                    .expect("failed to spawn tokio runtime thread");
+
+                NetworkExecutor {
+                    handle,
+                    runtime: None,
+                    shutdown: Some(shutdown_tx),
+                }
+            }
+            Self::Handle(handle) => NetworkExecutor {
+                handle: handle.clone(),
+                runtime: None,
+                shutdown: None,
+            },
+            Self::MultiThread { worker_threads } => {
+                let mut builder = tokio::runtime::Builder::new_multi_thread();
+                if let Some(worker_threads) = worker_threads {
+                    builder.worker_threads(*worker_threads);
+                }
+                let runtime = builder
+                    .enable_all()
+                    .thread_name("network")
+                    .build()
+                    .expect("failed to build tokio runtime");
+
+                NetworkExecutor {
+                    handle: runtime.handle().clone(),
+                    runtime: Some(runtime),
+                    shutdown: None,
+                }
+            }
+        }
+    }
+}
+
+/// Network Executor.
+///
+/// Keeps an owned [`NetworkRuntime`] alive for the lifetime of the app.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Resource)]
+pub struct NetworkExecutor {
+    handle: tokio::runtime::Handle,
+    runtime: Option<tokio::runtime::Runtime>,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl Drop for NetworkExecutor {
+    fn drop(&mut self) {
+        drop(self.shutdown.take());
+
+        // the app may be dropped from within an async context.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl NetworkExecutor {
+    /// Returns the handle of this [`NetworkExecutor`].
+    #[must_use]
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        &self.handle
+    }
+}
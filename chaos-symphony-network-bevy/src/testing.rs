@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+/// Calls the function until it returns a value.
+///
+/// # Panics
+///
+/// Will panic if no value is returned within a second.
+pub fn until<T>(mut f: impl FnMut() -> Option<T>) -> T {
+    for _ in 0..1000 {
+        if let Some(value) = f() {
+            return value;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("timed out");
+}
@@ -0,0 +1,419 @@
+use std::{
+    fs,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// Certificate file name used by [`CertificateSource::SelfSigned`].
+const CERTIFICATE_FILE_NAME: &str = "cert.der";
+
+/// Private key file name used by [`CertificateSource::SelfSigned`].
+const PRIVATE_KEY_FILE_NAME: &str = "key.der";
+
+/// Returns the default certificate directory.
+///
+/// Resolves to the local data directory of the application, e.g.
+/// `~/.local/share/chaos-symphony` on Linux.
+#[must_use]
+pub fn default_certificate_directory() -> Option<PathBuf> {
+    directories::ProjectDirs::from("uk.co", "agabani", "chaos-symphony")
+        .map(|dirs| dirs.data_local_dir().to_path_buf())
+}
+
+/// Resolves the [`default_certificate_directory`].
+fn resolve_default_certificate_directory() -> Result<PathBuf, io::Error> {
+    default_certificate_directory().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "unable to resolve default certificate directory, no home directory found",
+        )
+    })
+}
+
+/// Certificate Source.
+///
+/// Certificate chain and private key presented by an endpoint.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub enum CertificateSource {
+    /// DER encoded certificate and private key files in the
+    /// [`default_certificate_directory`], resolved on load.
+    DefaultDirectory,
+
+    /// DER encoded certificate and private key files.
+    Der {
+        /// Certificate.
+        certificate: PathBuf,
+
+        /// Private Key.
+        private_key: PathBuf,
+    },
+
+    /// In memory certificate chain and private key.
+    Memory {
+        /// Certificate Chain.
+        certificate_chain: Vec<rustls::Certificate>,
+
+        /// Private Key.
+        private_key: rustls::PrivateKey,
+    },
+
+    /// PEM encoded certificate chain and private key files.
+    Pem {
+        /// Certificate Chain.
+        certificate_chain: PathBuf,
+
+        /// Private Key.
+        private_key: PathBuf,
+    },
+
+    /// Self signed certificate.
+    ///
+    /// Generated on load. When a directory is present, the certificate is read
+    /// from the directory and only generated and written if missing.
+    SelfSigned {
+        /// Directory.
+        directory: Option<PathBuf>,
+
+        /// Subject Alt Names.
+        subject_alt_names: Vec<String>,
+    },
+}
+
+impl Default for CertificateSource {
+    /// DER encoded files in the [`default_certificate_directory`].
+    fn default() -> Self {
+        Self::DefaultDirectory
+    }
+}
+
+impl CertificateSource {
+    /// Generates an in memory self signed [`CertificateSource::Memory`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to generate certificate.
+    pub fn generate(subject_alt_names: Vec<String>) -> Result<Self, io::Error> {
+        let (certificate, private_key) = generate(subject_alt_names)?;
+        Ok(Self::Memory {
+            certificate_chain: vec![rustls::Certificate(certificate)],
+            private_key: rustls::PrivateKey(private_key),
+        })
+    }
+
+    /// Loads the certificate chain and private key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to resolve the default certificate
+    /// directory, or read, parse or generate certificate.
+    pub fn load(&self) -> Result<(Vec<rustls::Certificate>, rustls::PrivateKey), io::Error> {
+        match self {
+            CertificateSource::DefaultDirectory => {
+                let directory = resolve_default_certificate_directory()?;
+                CertificateSource::Der {
+                    certificate: directory.join(CERTIFICATE_FILE_NAME),
+                    private_key: directory.join(PRIVATE_KEY_FILE_NAME),
+                }
+                .load()
+            }
+            CertificateSource::Der {
+                certificate,
+                private_key,
+            } => Ok((
+                vec![rustls::Certificate(fs::read(certificate)?)],
+                rustls::PrivateKey(fs::read(private_key)?),
+            )),
+            CertificateSource::Memory {
+                certificate_chain,
+                private_key,
+            } => Ok((certificate_chain.clone(), private_key.clone())),
+            CertificateSource::Pem {
+                certificate_chain,
+                private_key,
+            } => Ok((
+                read_pem_certificates(certificate_chain)?,
+                read_pem_private_key(private_key)?,
+            )),
+            CertificateSource::SelfSigned {
+                directory: Some(directory),
+                subject_alt_names,
+            } => {
+                let certificate_path = directory.join(CERTIFICATE_FILE_NAME);
+                let private_key_path = directory.join(PRIVATE_KEY_FILE_NAME);
+
+                let (certificate, private_key) = match fs::read(&certificate_path)
+                    .and_then(|certificate| Ok((certificate, fs::read(&private_key_path)?)))
+                {
+                    Ok(value) => value,
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                        let (certificate, private_key) = generate(subject_alt_names.clone())?;
+                        fs::create_dir_all(directory)?;
+                        fs::write(&certificate_path, &certificate)?;
+                        fs::write(&private_key_path, &private_key)?;
+                        (certificate, private_key)
+                    }
+                    Err(error) => {
+                        return Err(error);
+                    }
+                };
+
+                Ok((
+                    vec![rustls::Certificate(certificate)],
+                    rustls::PrivateKey(private_key),
+                ))
+            }
+            CertificateSource::SelfSigned {
+                directory: None,
+                subject_alt_names,
+            } => {
+                let (certificate, private_key) = generate(subject_alt_names.clone())?;
+                Ok((
+                    vec![rustls::Certificate(certificate)],
+                    rustls::PrivateKey(private_key),
+                ))
+            }
+        }
+    }
+}
+
+/// Root Certificate Source.
+///
+/// Certificate authorities trusted by an endpoint.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub enum RootCertificateSource {
+    /// DER encoded certificate file in the [`default_certificate_directory`],
+    /// resolved on load.
+    DefaultDirectory,
+
+    /// DER encoded certificate file.
+    Der(PathBuf),
+
+    /// In memory certificates.
+    Memory(Vec<rustls::Certificate>),
+
+    /// PEM encoded certificate bundle file.
+    Pem(PathBuf),
+}
+
+impl Default for RootCertificateSource {
+    /// DER encoded file in the [`default_certificate_directory`].
+    fn default() -> Self {
+        Self::DefaultDirectory
+    }
+}
+
+impl RootCertificateSource {
+    /// Loads the root certificate store.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to resolve the default certificate
+    /// directory, or read or parse certificates.
+    pub fn load(&self) -> Result<rustls::RootCertStore, io::Error> {
+        let certificates = match self {
+            RootCertificateSource::DefaultDirectory => {
+                let directory = resolve_default_certificate_directory()?;
+                vec![rustls::Certificate(fs::read(
+                    directory.join(CERTIFICATE_FILE_NAME),
+                )?)]
+            }
+            RootCertificateSource::Der(path) => vec![rustls::Certificate(fs::read(path)?)],
+            RootCertificateSource::Memory(certificates) => certificates.clone(),
+            RootCertificateSource::Pem(path) => read_pem_certificates(path)?,
+        };
+
+        let mut roots = rustls::RootCertStore::empty();
+        for certificate in &certificates {
+            roots
+                .add(certificate)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        }
+        Ok(roots)
+    }
+}
+
+/// Client Authentication.
+///
+/// Verification applied by a server to client certificates.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default)]
+pub enum ClientAuthentication {
+    /// Client certificates are not requested.
+    #[default]
+    None,
+
+    /// Client certificates are verified when presented.
+    Optional(RootCertificateSource),
+
+    /// Client certificates are required and verified.
+    Required(RootCertificateSource),
+}
+
+/// Peer Certificate.
+///
+/// Verified end-entity certificate presented by the remote endpoint.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerCertificate {
+    common_name: Option<String>,
+    subject: String,
+    subject_alt_names: Vec<String>,
+}
+
+impl PeerCertificate {
+    /// Parses a DER encoded [`PeerCertificate`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to parse certificate.
+    pub fn parse(certificate: &rustls::Certificate) -> Result<Self, io::Error> {
+        let (_, certificate) = x509_parser::parse_x509_certificate(&certificate.0)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        let common_name = certificate
+            .subject()
+            .iter_common_name()
+            .find_map(|common_name| common_name.as_str().ok())
+            .map(ToString::to_string);
+
+        let subject_alt_names = certificate
+            .subject_alternative_name()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+            .map(|extension| {
+                extension
+                    .value
+                    .general_names
+                    .iter()
+                    .filter_map(|general_name| match general_name {
+                        x509_parser::extensions::GeneralName::DNSName(name)
+                        | x509_parser::extensions::GeneralName::URI(name) => {
+                            Some((*name).to_string())
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            common_name,
+            subject: certificate.subject().to_string(),
+            subject_alt_names,
+        })
+    }
+
+    /// Returns the common name of this [`PeerCertificate`].
+    #[must_use]
+    pub fn common_name(&self) -> Option<&str> {
+        self.common_name.as_deref()
+    }
+
+    /// Is the name either the common name or one of the subject alt names.
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        self.common_name() == Some(name)
+            || self
+                .subject_alt_names
+                .iter()
+                .any(|subject_alt_name| subject_alt_name == name)
+    }
+
+    /// Returns the subject of this [`PeerCertificate`].
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the subject alt names of this [`PeerCertificate`].
+    #[must_use]
+    pub fn subject_alt_names(&self) -> &[String] {
+        &self.subject_alt_names
+    }
+}
+
+/// Generates a self signed DER encoded certificate and private key.
+fn generate(subject_alt_names: Vec<String>) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
+    let certificate =
+        rcgen::generate_simple_self_signed(subject_alt_names).map_err(io::Error::other)?;
+    let private_key = certificate.serialize_private_key_der();
+    let certificate = certificate.serialize_der().map_err(io::Error::other)?;
+    Ok((certificate, private_key))
+}
+
+/// Reads PEM encoded certificates.
+fn read_pem_certificates(path: &Path) -> Result<Vec<rustls::Certificate>, io::Error> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let certificates = rustls_pemfile::certs(&mut reader)?;
+
+    if certificates.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no certificates found in {}", path.display()),
+        ));
+    }
+
+    Ok(certificates.into_iter().map(rustls::Certificate).collect())
+}
+
+/// Reads the first PEM encoded private key.
+fn read_pem_private_key(path: &Path) -> Result<rustls::PrivateKey, io::Error> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key),
+            ) => return Ok(rustls::PrivateKey(key)),
+            Some(_) => {}
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("no private key found in {}", path.display()),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{CertificateSource, RootCertificateSource};
+
+    #[test]
+    fn test_load_pem() {
+        // Arrange
+        let directory = std::env::temp_dir().join(format!(
+            "chaos-symphony-network-test-load-pem-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+
+        let certificate = rcgen::generate_simple_self_signed(["localhost".to_string()]).unwrap();
+        let certificate_path = directory.join("cert.pem");
+        let private_key_path = directory.join("key.pem");
+        fs::write(&certificate_path, certificate.serialize_pem().unwrap()).unwrap();
+        fs::write(&private_key_path, certificate.serialize_private_key_pem()).unwrap();
+
+        let certificate_source = CertificateSource::Pem {
+            certificate_chain: certificate_path.clone(),
+            private_key: private_key_path,
+        };
+        let root_certificate_source = RootCertificateSource::Pem(certificate_path);
+
+        // Act
+        let certificate_result = certificate_source.load();
+        let root_certificate_result = root_certificate_source.load();
+        fs::remove_dir_all(&directory).unwrap();
+
+        // Assert
+        let (certificate_chain, private_key) = certificate_result.unwrap();
+        assert_eq!(certificate_chain.len(), 1);
+        assert_eq!(private_key.0, certificate.serialize_private_key_der());
+        assert_eq!(root_certificate_result.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,54 @@
+/// Channel.
+///
+/// Messages sent on the same channel share a long-lived stream and are
+/// received in order. Messages sent on different channels are independent of
+/// each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Channel(u8);
+
+impl Channel {
+    /// Control.
+    ///
+    /// Ordered reliable channel for requests, responses and events.
+    pub const CONTROL: Channel = Channel(0);
+
+    /// Replication.
+    ///
+    /// Ordered reliable channel for bulk replication.
+    pub const REPLICATION: Channel = Channel(1);
+
+    /// Creates a new [`Channel`].
+    #[must_use]
+    pub const fn new(id: u8) -> Self {
+        Self(id)
+    }
+
+    /// Returns the id of this [`Channel`].
+    #[must_use]
+    pub fn id(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self::CONTROL
+    }
+}
+
+/// Reliability.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reliability {
+    /// Reliable.
+    ///
+    /// Retransmitted until acknowledged and received in order per [`Channel`].
+    #[default]
+    Reliable,
+
+    /// Unreliable.
+    ///
+    /// Sent as a datagram which may be lost, duplicated or reordered. Falls
+    /// back to [`Reliability::Reliable`] when the message does not fit in a
+    /// datagram.
+    Unreliable,
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Close Reason.
+///
+/// Application close code sent to the remote endpoint when closing a
+/// [`Connection`](crate::Connection).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Authentication Failed.
+    AuthenticationFailed,
+
+    /// Idle Timeout.
+    IdleTimeout,
+
+    /// Kicked.
+    Kicked,
+
+    /// Other.
+    ///
+    /// Close code unknown to this endpoint.
+    Other(u64),
+
+    /// Protocol Violation.
+    ProtocolViolation,
+
+    /// Server Shutdown.
+    ServerShutdown,
+}
+
+impl CloseReason {
+    /// Returns the code of this [`CloseReason`].
+    #[must_use]
+    pub fn code(self) -> u64 {
+        match self {
+            Self::ProtocolViolation => 1,
+            Self::AuthenticationFailed => 2,
+            Self::Kicked => 3,
+            Self::ServerShutdown => 4,
+            Self::IdleTimeout => 5,
+            Self::Other(code) => code,
+        }
+    }
+
+    /// Creates a [`CloseReason`] from its code.
+    #[must_use]
+    pub fn from_code(code: u64) -> Self {
+        match code {
+            1 => Self::ProtocolViolation,
+            2 => Self::AuthenticationFailed,
+            3 => Self::Kicked,
+            4 => Self::ServerShutdown,
+            5 => Self::IdleTimeout,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AuthenticationFailed => write!(f, "authentication failed"),
+            Self::IdleTimeout => write!(f, "idle timeout"),
+            Self::Kicked => write!(f, "kicked"),
+            Self::Other(code) => write!(f, "close code {code}"),
+            Self::ProtocolViolation => write!(f, "protocol violation"),
+            Self::ServerShutdown => write!(f, "server shutdown"),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Codec.
+pub trait Codec {
+    /// Encodes a value.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to serialize value.
+    fn encode<T>(value: &T) -> Result<Vec<u8>, CodecError>
+    where
+        T: Serialize;
+
+    /// Decodes a value.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to deserialize value.
+    fn decode<T>(buf: &[u8]) -> Result<T, CodecError>
+    where
+        T: DeserializeOwned;
+}
+
+/// Binary Codec.
+///
+/// Compact binary encoding using `postcard`.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryCodec;
+
+impl Codec for BinaryCodec {
+    fn encode<T>(value: &T) -> Result<Vec<u8>, CodecError>
+    where
+        T: Serialize,
+    {
+        postcard::to_stdvec(value).map_err(CodecError::Binary)
+    }
+
+    fn decode<T>(buf: &[u8]) -> Result<T, CodecError>
+    where
+        T: DeserializeOwned,
+    {
+        postcard::from_bytes(buf).map_err(CodecError::Binary)
+    }
+}
+
+/// Json Codec.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T>(value: &T) -> Result<Vec<u8>, CodecError>
+    where
+        T: Serialize,
+    {
+        serde_json::to_vec(value).map_err(CodecError::Json)
+    }
+
+    fn decode<T>(buf: &[u8]) -> Result<T, CodecError>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(buf).map_err(CodecError::Json)
+    }
+}
+
+/// Codec Error.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug)]
+pub enum CodecError {
+    /// Binary.
+    Binary(postcard::Error),
+
+    /// Json.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary(_) => write!(f, "binary codec failed"),
+            Self::Json(_) => write!(f, "json codec failed"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Binary(error) => Some(error),
+            Self::Json(error) => Some(error),
+        }
+    }
+}
+
+/// Encoding.
+///
+/// Negotiated per connection using ALPN.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// Binary.
+    #[default]
+    Binary,
+
+    /// Json.
+    Json,
+}
+
+impl Encoding {
+    /// Returns the ALPN protocol of this [`Encoding`].
+    #[must_use]
+    pub fn alpn_protocol(self) -> &'static [u8] {
+        match self {
+            Encoding::Binary => b"chaos-symphony/binary",
+            Encoding::Json => b"chaos-symphony/json",
+        }
+    }
+
+    /// Returns the [`Encoding`] of an ALPN protocol.
+    ///
+    /// Unrecognized and absent protocols fall back to [`Encoding::Json`].
+    #[must_use]
+    pub fn from_alpn_protocol(protocol: Option<&[u8]>) -> Self {
+        if protocol == Some(Encoding::Binary.alpn_protocol()) {
+            Encoding::Binary
+        } else {
+            Encoding::Json
+        }
+    }
+
+    /// Encodes a value using the [`Codec`] of this [`Encoding`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to serialize value.
+    pub fn encode<T>(self, value: &T) -> Result<Vec<u8>, CodecError>
+    where
+        T: Serialize,
+    {
+        match self {
+            Encoding::Binary => BinaryCodec::encode(value),
+            Encoding::Json => JsonCodec::encode(value),
+        }
+    }
+
+    /// Decodes a value using the [`Codec`] of this [`Encoding`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to deserialize value.
+    pub fn decode<T>(self, buf: &[u8]) -> Result<T, CodecError>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            Encoding::Binary => BinaryCodec::decode(buf),
+            Encoding::Json => JsonCodec::decode(buf),
+        }
+    }
+}
+
+/// Encoded.
+///
+/// Serializes bytes encoded by a [`Codec`] as embedded raw JSON in human
+/// readable formats and as bytes otherwise, so [`Encoding::Json`] messages do
+/// not carry their header and payload as arrays of numbers.
+pub(crate) mod encoded {
+    use serde::{de, ser, Deserialize as _, Deserializer, Serialize as _, Serializer};
+    use serde_json::value::RawValue;
+
+    /// Serializes the encoded bytes.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if human readable and bytes are not valid JSON.
+    pub(crate) fn serialize<S>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            let json = std::str::from_utf8(value).map_err(ser::Error::custom)?;
+            let raw: &RawValue = serde_json::from_str(json).map_err(ser::Error::custom)?;
+            raw.serialize(serializer)
+        } else {
+            serde_bytes::serialize(value, serializer)
+        }
+    }
+
+    /// Deserializes the encoded bytes.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to deserialize value.
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let raw = Box::<RawValue>::deserialize(deserializer).map_err(de::Error::custom)?;
+            Ok(raw.get().as_bytes().to_vec())
+        } else {
+            serde_bytes::deserialize(deserializer)
+        }
+    }
+}
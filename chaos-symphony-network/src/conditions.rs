@@ -0,0 +1,494 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, Rng as _, SeedableRng as _};
+use tokio::time::Instant;
+
+use crate::{
+    BoxFuture, Channel, CloseReason, ConnectionStats, RecvError, Reliability, SendError, Transport,
+};
+
+/// Maximum number of frames scheduled for delivery per connection.
+///
+/// Sends wait while the delivery queue is full.
+const MAX_SCHEDULED: usize = 1024;
+
+/// Network Conditions.
+///
+/// Simulated link characteristics applied to frames sent by a connection.
+/// Reliable frames are never lost, duplicated or reordered; lost reliable
+/// frames are delayed as if retransmitted instead.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetworkConditions {
+    bandwidth: Option<u64>,
+    duplication: f64,
+    jitter: Duration,
+    latency: Duration,
+    loss: f64,
+    reordering: f64,
+    seed: Option<u64>,
+}
+
+impl NetworkConditions {
+    /// Returns the bandwidth of this [`NetworkConditions`].
+    ///
+    /// Bytes per second, unlimited when absent.
+    #[must_use]
+    pub fn bandwidth(&self) -> Option<u64> {
+        self.bandwidth
+    }
+
+    /// Returns the duplication of this [`NetworkConditions`].
+    ///
+    /// Probability of an unreliable frame being delivered twice.
+    #[must_use]
+    pub fn duplication(&self) -> f64 {
+        self.duplication
+    }
+
+    /// Returns the jitter of this [`NetworkConditions`].
+    ///
+    /// Maximum random delay added to the latency.
+    #[must_use]
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Returns the latency of this [`NetworkConditions`].
+    ///
+    /// One way delay.
+    #[must_use]
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Returns the loss of this [`NetworkConditions`].
+    ///
+    /// Probability of a frame being lost.
+    #[must_use]
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+
+    /// Returns the reordering of this [`NetworkConditions`].
+    ///
+    /// Probability of an unreliable frame being held back behind later frames.
+    #[must_use]
+    pub fn reordering(&self) -> f64 {
+        self.reordering
+    }
+
+    /// Returns the seed of this [`NetworkConditions`].
+    ///
+    /// Makes the simulation deterministic when present.
+    #[must_use]
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// With bandwidth.
+    #[must_use]
+    pub fn with_bandwidth(mut self, bandwidth: u64) -> Self {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+
+    /// With duplication.
+    #[must_use]
+    pub fn with_duplication(mut self, duplication: f64) -> Self {
+        self.duplication = duplication;
+        self
+    }
+
+    /// With jitter.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// With latency.
+    #[must_use]
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// With loss.
+    #[must_use]
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    /// With reordering.
+    #[must_use]
+    pub fn with_reordering(mut self, reordering: f64) -> Self {
+        self.reordering = reordering;
+        self
+    }
+
+    /// With seed.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// Conditioned Transport.
+///
+/// Wraps a [`Transport`], delaying and dropping sent frames according to the
+/// [`NetworkConditions`].
+#[derive(Debug)]
+pub(crate) struct ConditionedTransport {
+    conditions: NetworkConditions,
+    error: Arc<Mutex<Option<SendError>>>,
+    inner: Arc<dyn Transport>,
+    sender: tokio::sync::mpsc::Sender<Scheduled>,
+    state: Mutex<ConditionedTransportState>,
+}
+
+impl ConditionedTransport {
+    /// Creates a new [`ConditionedTransport`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called outside of a tokio runtime.
+    pub(crate) fn new(inner: Arc<dyn Transport>, conditions: NetworkConditions) -> Self {
+        let (sender, receiver) = tokio::sync::mpsc::channel(MAX_SCHEDULED);
+        let error = Arc::default();
+        tokio::spawn(deliver(inner.clone(), receiver, Arc::clone(&error)));
+
+        let rng = match conditions.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Self {
+            conditions,
+            error,
+            inner,
+            sender,
+            state: Mutex::new(ConditionedTransportState {
+                last: Instant::now(),
+                reliable: HashMap::new(),
+                rng,
+                sequence: 0,
+                transmitted: Instant::now(),
+            }),
+        }
+    }
+
+    /// Schedules the delivery of a frame.
+    ///
+    /// Returns the frames to deliver, none when lost.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[allow(clippy::cast_precision_loss)]
+    fn schedule(&self, channel: Channel, reliability: Reliability, buf: Vec<u8>) -> Vec<Scheduled> {
+        let conditions = &self.conditions;
+        let mut state = self.state.lock().expect("poisoned");
+        let state = &mut *state;
+
+        let now = Instant::now();
+
+        // frames are transmitted one after another at the bandwidth.
+        let transmitted = state.transmitted.max(now)
+            + conditions.bandwidth.map_or(Duration::ZERO, |bandwidth| {
+                Duration::from_secs_f64(buf.len() as f64 / bandwidth.max(1) as f64)
+            });
+        state.transmitted = transmitted;
+
+        let mut delay = conditions.latency + conditions.jitter.mul_f64(state.rng.gen::<f64>());
+        let is_lost = state.rng.gen::<f64>() < conditions.loss;
+
+        let mut is_duplicated = false;
+        match reliability {
+            Reliability::Reliable => {
+                if is_lost {
+                    // retransmitted after a round trip.
+                    delay += conditions.latency * 2 + conditions.jitter;
+                }
+            }
+            Reliability::Unreliable => {
+                if is_lost {
+                    return Vec::new();
+                }
+                if state.rng.gen::<f64>() < conditions.reordering {
+                    delay += conditions.latency + conditions.jitter + Duration::from_millis(1);
+                }
+                is_duplicated = state.rng.gen::<f64>() < conditions.duplication;
+            }
+        }
+
+        let mut at = transmitted + delay;
+        if reliability == Reliability::Reliable {
+            // reliable frames are delivered in order per channel.
+            let previous = state.reliable.entry(channel).or_insert(at);
+            at = at.max(*previous);
+            *previous = at;
+        }
+
+        state.last = state.last.max(at);
+
+        let mut scheduled = Vec::new();
+        if is_duplicated {
+            state.sequence += 1;
+            scheduled.push(Scheduled {
+                at,
+                frame: Frame::Data {
+                    buf: buf.clone(),
+                    channel,
+                    reliability,
+                },
+                sequence: state.sequence,
+            });
+        }
+
+        state.sequence += 1;
+        scheduled.push(Scheduled {
+            at,
+            frame: Frame::Data {
+                buf,
+                channel,
+                reliability,
+            },
+            sequence: state.sequence,
+        });
+        scheduled
+    }
+
+    /// Schedules closing the inner transport after the last scheduled frame.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    fn schedule_close(
+        &self,
+        reason: CloseReason,
+        message: String,
+        sender: tokio::sync::oneshot::Sender<()>,
+    ) -> Scheduled {
+        let mut state = self.state.lock().expect("poisoned");
+
+        state.sequence += 1;
+        Scheduled {
+            at: state.last.max(Instant::now()),
+            frame: Frame::Close {
+                message,
+                reason,
+                sender,
+            },
+            sequence: state.sequence,
+        }
+    }
+
+    /// Takes the error of the inner transport, if any.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    fn take_error(&self) -> Option<SendError> {
+        self.error.lock().expect("poisoned").take()
+    }
+}
+
+impl Transport for ConditionedTransport {
+    fn close<'a>(&'a self, reason: CloseReason, message: &'a str) -> BoxFuture<'a, ()> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let scheduled = self.schedule_close(reason, message.to_string(), sender);
+        Box::pin(async move {
+            // fails once the inner transport failed, when there is nothing to close.
+            if self.sender.send(scheduled).await.is_ok() {
+                let _ = receiver.await;
+            }
+        })
+    }
+
+    fn id(&self) -> usize {
+        self.inner.id()
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Result<Vec<u8>, RecvError>> {
+        self.inner.recv()
+    }
+
+    fn remote_address(&self) -> SocketAddr {
+        self.inner.remote_address()
+    }
+
+    fn send(
+        &self,
+        channel: Channel,
+        reliability: Reliability,
+        buf: Vec<u8>,
+    ) -> BoxFuture<'_, Result<(), SendError>> {
+        Box::pin(async move {
+            // frames are sent by the inner transport later, so its error fails the next send.
+            if let Some(error) = self.take_error() {
+                return Err(error);
+            }
+            if self.sender.is_closed() {
+                return Err(SendError::Closed);
+            }
+
+            for scheduled in self.schedule(channel, reliability, buf) {
+                self.sender
+                    .send(scheduled)
+                    .await
+                    .map_err(|_| SendError::Closed)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        self.inner.stats()
+    }
+}
+
+/// Conditioned Transport State.
+#[derive(Debug)]
+struct ConditionedTransportState {
+    last: Instant,
+    reliable: HashMap<Channel, Instant>,
+    rng: StdRng,
+    sequence: u64,
+    transmitted: Instant,
+}
+
+/// Frame.
+#[derive(Debug)]
+enum Frame {
+    /// Close.
+    Close {
+        message: String,
+        reason: CloseReason,
+        sender: tokio::sync::oneshot::Sender<()>,
+    },
+
+    /// Data.
+    Data {
+        buf: Vec<u8>,
+        channel: Channel,
+        reliability: Reliability,
+    },
+}
+
+/// Scheduled frame.
+#[derive(Debug)]
+struct Scheduled {
+    at: Instant,
+    frame: Frame,
+    sequence: u64,
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scheduled {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.sequence).cmp(&(other.at, other.sequence))
+    }
+}
+
+/// Delivers scheduled frames to the inner transport when due.
+///
+/// Frames still scheduled when the transport is dropped are delivered before
+/// returning. Stops at the first error of the inner transport, keeping it for
+/// the next send.
+///
+/// # Panics
+///
+/// Will panic if [`Mutex`] is poisoned.
+async fn deliver(
+    inner: Arc<dyn Transport>,
+    mut receiver: tokio::sync::mpsc::Receiver<Scheduled>,
+    error: Arc<Mutex<Option<SendError>>>,
+) {
+    let mut queue = BinaryHeap::new();
+
+    let result = async {
+        loop {
+            let next = queue
+                .peek()
+                .map(|Reverse(scheduled): &Reverse<Scheduled>| scheduled.at);
+
+            tokio::select! {
+                scheduled = receiver.recv(), if queue.len() < MAX_SCHEDULED => match scheduled {
+                    Some(scheduled) => queue.push(Reverse(scheduled)),
+                    None => break,
+                },
+                () = sleep_until(next) => {
+                    if let Some(Reverse(scheduled)) = queue.pop() {
+                        dispatch(&*inner, scheduled.frame).await?;
+                    }
+                }
+            }
+        }
+
+        while let Some(Reverse(scheduled)) = queue.pop() {
+            tokio::time::sleep_until(scheduled.at).await;
+            dispatch(&*inner, scheduled.frame).await?;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    if let Err(inner_error) = result {
+        *error.lock().expect("poisoned") = Some(inner_error);
+    }
+}
+
+/// Dispatches a due frame to the inner transport.
+///
+/// # Errors
+///
+/// Will return `Err` if the inner transport is unable to send.
+async fn dispatch(inner: &dyn Transport, frame: Frame) -> Result<(), SendError> {
+    match frame {
+        Frame::Close {
+            message,
+            reason,
+            sender,
+        } => {
+            inner.close(reason, &message).await;
+            let _ = sender.send(());
+            Ok(())
+        }
+        Frame::Data {
+            buf,
+            channel,
+            reliability,
+        } => inner.send(channel, reliability, buf).await,
+    }
+}
+
+/// Sleeps until the deadline, or forever when absent.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
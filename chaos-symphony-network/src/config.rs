@@ -0,0 +1,380 @@
+use std::net::{Ipv6Addr, SocketAddr};
+
+use crate::{
+    CertificateSource, ClientAuthentication, Encoding, MemoryNetwork, NetworkConditions,
+    RootCertificateSource,
+};
+
+/// Default ALPN Protocols.
+///
+/// Prefers [`Encoding::Binary`] over [`Encoding::Json`].
+fn default_alpn_protocols() -> Vec<Vec<u8>> {
+    alpn_protocols(&[Encoding::Binary, Encoding::Json])
+}
+
+/// ALPN Protocols.
+fn alpn_protocols(encodings: &[Encoding]) -> Vec<Vec<u8>> {
+    encodings
+        .iter()
+        .map(|encoding| encoding.alpn_protocol().to_vec())
+        .collect()
+}
+
+/// Limits.
+///
+/// Bounds the resources a peer may consume on a connection.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    max_concurrent_uni_streams: u32,
+    max_frame_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_uni_streams: 16,
+            max_frame_size: 1024 * 1024,
+        }
+    }
+}
+
+impl Limits {
+    /// Returns the max concurrent uni streams of this [`Limits`].
+    ///
+    /// Maximum number of inbound streams, one is used per [`Channel`](crate::Channel).
+    #[must_use]
+    pub fn max_concurrent_uni_streams(&self) -> u32 {
+        self.max_concurrent_uni_streams
+    }
+
+    /// Returns the max frame size of this [`Limits`].
+    ///
+    /// Maximum size in bytes of an encoded message.
+    #[must_use]
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+
+    /// With max concurrent uni streams.
+    #[must_use]
+    pub fn with_max_concurrent_uni_streams(mut self, max_concurrent_uni_streams: u32) -> Self {
+        self.max_concurrent_uni_streams = max_concurrent_uni_streams;
+        self
+    }
+
+    /// With max frame size.
+    #[must_use]
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+}
+
+/// Transport Config.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default)]
+pub enum TransportConfig {
+    /// QUIC.
+    #[default]
+    Quic,
+
+    /// In-process [`MemoryNetwork`].
+    Memory(MemoryNetwork),
+}
+
+/// Client Config.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    alpn_protocols: Vec<Vec<u8>>,
+    certificate: Option<CertificateSource>,
+    conditions: Option<NetworkConditions>,
+    limits: Limits,
+    listen_address: SocketAddr,
+    remote_address: SocketAddr,
+    root_certificates: RootCertificateSource,
+    server_name: String,
+    transport: TransportConfig,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            alpn_protocols: default_alpn_protocols(),
+            certificate: None,
+            conditions: None,
+            limits: Limits::default(),
+            listen_address: SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+            remote_address: SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 4433),
+            root_certificates: RootCertificateSource::default(),
+            server_name: "localhost".to_string(),
+            transport: TransportConfig::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Returns the ALPN protocols of this [`ClientConfig`].
+    #[must_use]
+    pub fn alpn_protocols(&self) -> &[Vec<u8>] {
+        &self.alpn_protocols
+    }
+
+    /// Returns the certificate of this [`ClientConfig`].
+    ///
+    /// Presented to servers requesting client authentication.
+    #[must_use]
+    pub fn certificate(&self) -> Option<&CertificateSource> {
+        self.certificate.as_ref()
+    }
+
+    /// Returns the network conditions of this [`ClientConfig`].
+    ///
+    /// Simulated on messages sent by connections when present.
+    #[must_use]
+    pub fn conditions(&self) -> Option<NetworkConditions> {
+        self.conditions
+    }
+
+    /// Returns the limits of this [`ClientConfig`].
+    #[must_use]
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the listen address of this [`ClientConfig`].
+    #[must_use]
+    pub fn listen_address(&self) -> SocketAddr {
+        self.listen_address
+    }
+
+    /// Returns the remote address of this [`ClientConfig`].
+    #[must_use]
+    pub fn remote_address(&self) -> SocketAddr {
+        self.remote_address
+    }
+
+    /// Returns the root certificates of this [`ClientConfig`].
+    #[must_use]
+    pub fn root_certificates(&self) -> &RootCertificateSource {
+        &self.root_certificates
+    }
+
+    /// Returns the server name of this [`ClientConfig`].
+    #[must_use]
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    /// Returns the transport of this [`ClientConfig`].
+    #[must_use]
+    pub fn transport(&self) -> &TransportConfig {
+        &self.transport
+    }
+
+    /// With ALPN protocols.
+    #[must_use]
+    pub fn with_alpn_protocols(mut self, alpn_protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = alpn_protocols;
+        self
+    }
+
+    /// With certificate.
+    #[must_use]
+    pub fn with_certificate(mut self, certificate: CertificateSource) -> Self {
+        self.certificate = Some(certificate);
+        self
+    }
+
+    /// With network conditions.
+    #[must_use]
+    pub fn with_conditions(mut self, conditions: NetworkConditions) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+
+    /// With encodings.
+    ///
+    /// Sets the ALPN protocols to the encodings in order of preference.
+    #[must_use]
+    pub fn with_encodings(mut self, encodings: &[Encoding]) -> Self {
+        self.alpn_protocols = alpn_protocols(encodings);
+        self
+    }
+
+    /// With limits.
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// With listen address.
+    #[must_use]
+    pub fn with_listen_address(mut self, listen_address: SocketAddr) -> Self {
+        self.listen_address = listen_address;
+        self
+    }
+
+    /// With remote address.
+    #[must_use]
+    pub fn with_remote_address(mut self, remote_address: SocketAddr) -> Self {
+        self.remote_address = remote_address;
+        self
+    }
+
+    /// With root certificates.
+    #[must_use]
+    pub fn with_root_certificates(mut self, root_certificates: RootCertificateSource) -> Self {
+        self.root_certificates = root_certificates;
+        self
+    }
+
+    /// With server name.
+    #[must_use]
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = server_name.into();
+        self
+    }
+
+    /// With transport.
+    #[must_use]
+    pub fn with_transport(mut self, transport: TransportConfig) -> Self {
+        self.transport = transport;
+        self
+    }
+}
+
+/// Server Config.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    alpn_protocols: Vec<Vec<u8>>,
+    certificate: CertificateSource,
+    client_authentication: ClientAuthentication,
+    conditions: Option<NetworkConditions>,
+    limits: Limits,
+    listen_address: SocketAddr,
+    transport: TransportConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            alpn_protocols: default_alpn_protocols(),
+            certificate: CertificateSource::default(),
+            client_authentication: ClientAuthentication::default(),
+            conditions: None,
+            limits: Limits::default(),
+            listen_address: SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 4433),
+            transport: TransportConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Returns the ALPN protocols of this [`ServerConfig`].
+    #[must_use]
+    pub fn alpn_protocols(&self) -> &[Vec<u8>] {
+        &self.alpn_protocols
+    }
+
+    /// Returns the certificate of this [`ServerConfig`].
+    #[must_use]
+    pub fn certificate(&self) -> &CertificateSource {
+        &self.certificate
+    }
+
+    /// Returns the client authentication of this [`ServerConfig`].
+    #[must_use]
+    pub fn client_authentication(&self) -> &ClientAuthentication {
+        &self.client_authentication
+    }
+
+    /// Returns the network conditions of this [`ServerConfig`].
+    ///
+    /// Simulated on messages sent by connections when present.
+    #[must_use]
+    pub fn conditions(&self) -> Option<NetworkConditions> {
+        self.conditions
+    }
+
+    /// Returns the limits of this [`ServerConfig`].
+    #[must_use]
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the listen address of this [`ServerConfig`].
+    #[must_use]
+    pub fn listen_address(&self) -> SocketAddr {
+        self.listen_address
+    }
+
+    /// Returns the transport of this [`ServerConfig`].
+    #[must_use]
+    pub fn transport(&self) -> &TransportConfig {
+        &self.transport
+    }
+
+    /// With ALPN protocols.
+    #[must_use]
+    pub fn with_alpn_protocols(mut self, alpn_protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = alpn_protocols;
+        self
+    }
+
+    /// With certificate.
+    #[must_use]
+    pub fn with_certificate(mut self, certificate: CertificateSource) -> Self {
+        self.certificate = certificate;
+        self
+    }
+
+    /// With client authentication.
+    #[must_use]
+    pub fn with_client_authentication(
+        mut self,
+        client_authentication: ClientAuthentication,
+    ) -> Self {
+        self.client_authentication = client_authentication;
+        self
+    }
+
+    /// With network conditions.
+    #[must_use]
+    pub fn with_conditions(mut self, conditions: NetworkConditions) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+
+    /// With encodings.
+    ///
+    /// Sets the ALPN protocols to the encodings in order of preference.
+    #[must_use]
+    pub fn with_encodings(mut self, encodings: &[Encoding]) -> Self {
+        self.alpn_protocols = alpn_protocols(encodings);
+        self
+    }
+
+    /// With limits.
+    #[must_use]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// With listen address.
+    #[must_use]
+    pub fn with_listen_address(mut self, listen_address: SocketAddr) -> Self {
+        self.listen_address = listen_address;
+        self
+    }
+
+    /// With transport.
+    #[must_use]
+    pub fn with_transport(mut self, transport: TransportConfig) -> Self {
+        self.transport = transport;
+        self
+    }
+}
@@ -3,7 +3,34 @@
 
 //! Chaos Symphony Network
 
-use std::{fs, io, net::SocketAddr, sync::Arc};
+mod certificate;
+mod channel;
+mod close;
+mod codec;
+mod conditions;
+mod config;
+mod memory;
+mod quic;
+mod stats;
+mod transport;
+
+use std::{
+    fmt,
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+pub use certificate::*;
+pub use channel::*;
+pub use close::CloseReason;
+pub use codec::*;
+pub use conditions::NetworkConditions;
+pub use config::*;
+pub use memory::MemoryNetwork;
+pub use stats::ConnectionStats;
+use transport::{BoxFuture, Transport};
 
 /// Accept Error.
 #[derive(Debug)]
@@ -12,10 +39,34 @@ pub enum AcceptError {
     Connection(quinn::ConnectionError),
 }
 
+impl fmt::Display for AcceptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connection(_) => write!(f, "handshake failed"),
+        }
+    }
+}
+
+impl std::error::Error for AcceptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connection(error) => Some(error),
+        }
+    }
+}
+
 /// Client.
 #[derive(Debug, Clone)]
 pub struct Client {
-    inner: quinn::Endpoint,
+    config: ClientConfig,
+    inner: ClientInner,
+}
+
+/// Client Inner.
+#[derive(Debug, Clone)]
+enum ClientInner {
+    Memory(MemoryNetwork),
+    Quic(quinn::Endpoint),
 }
 
 impl Client {
@@ -24,53 +75,45 @@ impl Client {
     /// # Errors
     ///
     /// Will return `Err` if unable to bind to port or find certificate.
-    ///
-    /// # Panics
-    ///
-    /// Will panic if unable to parse socket address.
-    pub fn new() -> Result<Self, io::Error> {
-        let config = Self::config()?;
-        let mut inner = quinn::Endpoint::client("[::]:0".parse().unwrap())?;
-        inner.set_default_client_config(config);
-        Ok(Self { inner })
+    pub fn new(config: ClientConfig) -> Result<Self, io::Error> {
+        let inner = match config.transport() {
+            TransportConfig::Memory(network) => ClientInner::Memory(network.clone()),
+            TransportConfig::Quic => ClientInner::Quic(quic::client(&config)?),
+        };
+        Ok(Self { config, inner })
     }
 
     /// Connect.
     ///
+    /// Connects to the remote address of the [`ClientConfig`].
+    ///
     /// # Errors
     ///
     /// Will return `Err` if unable to connect to server.
-    ///
-    /// # Panics
-    ///
-    /// Will panic if unable to parse socket address.
     pub fn connect(&self) -> Result<Connecting, ConnectError> {
-        let inner = self
-            .inner
-            .connect("[::1]:4433".parse().unwrap(), "localhost")
-            .map_err(ConnectError::Connect)?;
-        Ok(Connecting { inner })
+        self.connect_to(self.config.remote_address(), self.config.server_name())
     }
 
-    fn config() -> Result<quinn::ClientConfig, io::Error> {
-        let dirs = directories::ProjectDirs::from("uk.co", "agabani", "chaos-symphony").unwrap();
-        let path = dirs.data_local_dir();
-        let cert_path = path.join("cert.der");
-
-        let cert = fs::read(cert_path)?;
-        let cert = rustls::Certificate(cert);
-
-        let mut roots = rustls::RootCertStore::empty();
-        roots.add(&cert).unwrap();
-
-        let mut config = rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(roots)
-            .with_no_client_auth();
-        config.alpn_protocols = vec![b"hq-29".into()];
-
-        let config = quinn::ClientConfig::new(Arc::new(config));
-        Ok(config)
+    /// Connect to.
+    ///
+    /// Connects to the remote address, verifying the server certificate
+    /// against the server name.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to connect to server.
+    pub fn connect_to(
+        &self,
+        remote_address: SocketAddr,
+        server_name: &str,
+    ) -> Result<Connecting, ConnectError> {
+        let connecting = match &self.inner {
+            ClientInner::Memory(network) => network.connect(&self.config, remote_address)?,
+            ClientInner::Quic(endpoint) => {
+                quic::connect(endpoint, &self.config, remote_address, server_name)?
+            }
+        };
+        Ok(connecting.with_conditions(self.config.conditions()))
     }
 }
 
@@ -79,81 +122,297 @@ impl Client {
 pub enum ConnectError {
     /// Connect.
     Connect(quinn::ConnectError),
+
+    /// Refused.
+    ///
+    /// No server is listening on the remote address.
+    Refused,
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect(_) => write!(f, "unable to connect"),
+            Self::Refused => write!(f, "connection refused"),
+        }
+    }
+}
+
+impl std::error::Error for ConnectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Connect(error) => Some(error),
+            Self::Refused => None,
+        }
+    }
 }
 
 /// Connecting.
-#[derive(Debug)]
 pub struct Connecting {
-    inner: quinn::Connecting,
+    inner: BoxFuture<'static, Result<Connection, AcceptError>>,
+    remote_address: SocketAddr,
+}
+
+impl fmt::Debug for Connecting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connecting")
+            .field("remote_address", &self.remote_address)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Connecting {
+    /// Creates a new [`Connecting`].
+    fn new(
+        remote_address: SocketAddr,
+        future: impl Future<Output = Result<Connection, AcceptError>> + Send + 'static,
+    ) -> Self {
+        Self {
+            inner: Box::pin(future),
+            remote_address,
+        }
+    }
+
     /// Accept.
     ///
     /// # Errors
     ///
     /// Will return `Err` if connection is lost.
     pub async fn accept(self) -> Result<Connection, AcceptError> {
-        let inner = self.inner.await.map_err(AcceptError::Connection)?;
-        Ok(Connection { inner })
+        self.inner.await
+    }
+
+    /// Applies the network conditions, if any, to the accepted [`Connection`].
+    fn with_conditions(self, conditions: Option<NetworkConditions>) -> Self {
+        match conditions {
+            Some(conditions) => Self::new(self.remote_address, async move {
+                Ok(self.inner.await?.with_conditions(conditions))
+            }),
+            None => self,
+        }
     }
 
     /// Returns the remote address of this [`Connecting`].
     #[must_use]
     pub fn remote_address(&self) -> SocketAddr {
-        self.inner.remote_address()
+        self.remote_address
     }
 }
 
 /// Connection.
 #[derive(Debug, Clone)]
 pub struct Connection {
-    inner: quinn::Connection,
+    close_reason: Arc<Mutex<Option<CloseReason>>>,
+    encoding: Encoding,
+    limits: Limits,
+    messages: Arc<Mutex<stats::MessageCounts>>,
+    peer_certificate: Option<PeerCertificate>,
+    transport: Arc<dyn Transport>,
 }
 
 impl Connection {
+    /// Creates a new [`Connection`].
+    fn new(
+        transport: Arc<dyn Transport>,
+        encoding: Encoding,
+        limits: Limits,
+        peer_certificate: Option<PeerCertificate>,
+    ) -> Self {
+        Self {
+            close_reason: Arc::default(),
+            encoding,
+            limits,
+            messages: Arc::default(),
+            peer_certificate,
+            transport,
+        }
+    }
+
+    /// With network conditions.
+    ///
+    /// Simulates the network conditions on messages sent by this
+    /// [`Connection`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called outside of a tokio runtime.
+    #[must_use]
+    pub fn with_conditions(mut self, conditions: NetworkConditions) -> Self {
+        self.transport = Arc::new(conditions::ConditionedTransport::new(
+            self.transport,
+            conditions,
+        ));
+        self
+    }
+
+    /// Close.
+    ///
+    /// Delivers messages already sent, then closes the connection with the
+    /// reason and message, received by the remote endpoint as
+    /// [`RecvError::ApplicationClosed`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub async fn close(&self, reason: CloseReason, message: &str) {
+        self.close_reason
+            .lock()
+            .expect("poisoned")
+            .get_or_insert(reason);
+        self.transport.close(reason, message).await;
+    }
+
+    /// Returns the close reason of this [`Connection`].
+    ///
+    /// Present once closed by either endpoint with a reason or timed out.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[must_use]
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        *self.close_reason.lock().expect("poisoned")
+    }
+
+    /// Returns the encoding of this [`Connection`].
+    ///
+    /// Negotiated using ALPN.
+    #[must_use]
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
     /// Returns the id of this [`Connection`].
     #[must_use]
     pub fn id(&self) -> usize {
-        self.inner.stable_id()
+        self.transport.id()
+    }
+
+    /// Returns the peer certificate of this [`Connection`].
+    ///
+    /// Present when the remote endpoint authenticated with a verified
+    /// certificate.
+    #[must_use]
+    pub fn peer_certificate(&self) -> Option<&PeerCertificate> {
+        self.peer_certificate.as_ref()
     }
 
     /// Recv.
     ///
+    /// Messages are received in order per [`Channel`].
+    ///
     /// # Errors
     ///
     /// Will return `Err` if connection is lost or unable to read.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
     pub async fn recv(&self) -> Result<Message, RecvError> {
-        let (_, mut recv) = self
-            .inner
-            .accept_bi()
-            .await
-            .map_err(RecvError::Connection)?;
-        let buf = recv
-            .read_to_end(usize::MAX)
-            .await
-            .map_err(RecvError::Read)?;
-        serde_json::from_slice(&buf).map_err(RecvError::Json)
+        let buf = self.transport.recv().await.inspect_err(|error| {
+            if let Some(reason) = error.close_reason() {
+                self.close_reason
+                    .lock()
+                    .expect("poisoned")
+                    .get_or_insert(reason);
+            }
+        })?;
+        let message: Message = self.encoding.decode(&buf).map_err(RecvError::Codec)?;
+        self.messages
+            .lock()
+            .expect("poisoned")
+            .received(&message.endpoint);
+        Ok(message)
     }
 
     /// Returns the remote address of this [`Connection`].
     #[must_use]
     pub fn remote_address(&self) -> SocketAddr {
-        self.inner.remote_address()
+        self.transport.remote_address()
     }
 
     /// Send.
     ///
+    /// Sends on [`Channel::CONTROL`].
+    ///
     /// # Errors
     ///
-    /// Will return `Err` if connection is lost or unable to write.
+    /// Will return `Err` if connection is lost, unable to write or message
+    /// exceeds the maximum frame size.
     pub async fn send(&self, message: Message) -> Result<(), SendError> {
-        let buf = serde_json::to_vec(&message).map_err(SendError::Json)?;
-        let (mut send, _) = self.inner.open_bi().await.map_err(SendError::Connection)?;
-        send.write_all(&buf).await.map_err(SendError::Write)?;
-        send.finish().await.map_err(SendError::Write)?;
+        self.send_on(Channel::CONTROL, message).await
+    }
+
+    /// Send on channel.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if connection is lost, unable to write or message
+    /// exceeds the maximum frame size.
+    pub async fn send_on(&self, channel: Channel, message: Message) -> Result<(), SendError> {
+        self.send_with(channel, Reliability::Reliable, message)
+            .await
+    }
+
+    /// Send unreliable.
+    ///
+    /// Sends as a datagram, falling back to the channel when the message does
+    /// not fit in a datagram or datagrams are unsupported by the peer.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if connection is lost, unable to write or message
+    /// exceeds the maximum frame size.
+    pub async fn send_unreliable(
+        &self,
+        channel: Channel,
+        message: Message,
+    ) -> Result<(), SendError> {
+        self.send_with(channel, Reliability::Unreliable, message)
+            .await
+    }
+
+    /// Send with reliability.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if connection is lost, unable to write or message
+    /// exceeds the maximum frame size.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub async fn send_with(
+        &self,
+        channel: Channel,
+        reliability: Reliability,
+        message: Message,
+    ) -> Result<(), SendError> {
+        let buf = self.encoding.encode(&message).map_err(SendError::Codec)?;
+        if buf.len() > self.limits.max_frame_size() {
+            return Err(SendError::TooLarge);
+        }
+        self.transport.send(channel, reliability, buf).await?;
+        self.messages
+            .lock()
+            .expect("poisoned")
+            .sent(&message.endpoint);
         Ok(())
     }
+
+    /// Returns the stats of this [`Connection`].
+    ///
+    /// Link statistics are reported by QUIC connections only.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    #[must_use]
+    pub fn stats(&self) -> ConnectionStats {
+        let mut stats = self.transport.stats();
+        self.messages.lock().expect("poisoned").apply(&mut stats);
+        stats
+    }
 }
 
 /// Message.
@@ -166,42 +425,153 @@ pub struct Message {
     pub endpoint: String,
 
     /// Header.
-    pub header: String,
+    #[serde(with = "codec::encoded")]
+    pub header: Vec<u8>,
 
     /// Payload.
-    pub payload: String,
+    #[serde(with = "codec::encoded")]
+    pub payload: Vec<u8>,
 }
 
-/// Send Error.
+/// Recv Error.
 #[derive(Debug)]
 pub enum RecvError {
+    /// Application Closed.
+    ///
+    /// Connection was closed by the remote endpoint with a reason and
+    /// message.
+    ApplicationClosed(CloseReason, String),
+
+    /// Closed.
+    ///
+    /// Connection was closed by the transport.
+    Closed,
+
+    /// Codec.
+    Codec(CodecError),
+
     /// Connection.
     Connection(quinn::ConnectionError),
 
-    /// Json.
-    Json(serde_json::Error),
-
     /// Read.
-    Read(quinn::ReadToEndError),
+    Read(quinn::ReadExactError),
+
+    /// Too Large.
+    ///
+    /// Peer exceeded the maximum frame size and the connection was closed.
+    TooLarge,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ApplicationClosed(reason, message) if message.is_empty() => {
+                write!(f, "connection closed by remote endpoint: {reason}")
+            }
+            Self::ApplicationClosed(reason, message) => {
+                write!(
+                    f,
+                    "connection closed by remote endpoint: {reason}: {message}"
+                )
+            }
+            Self::Closed => write!(f, "connection closed"),
+            Self::Codec(_) => write!(f, "unable to decode message"),
+            Self::Connection(_) => write!(f, "connection lost"),
+            Self::Read(_) => write!(f, "unable to read frame"),
+            Self::TooLarge => write!(f, "frame exceeds the maximum frame size"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Codec(error) => Some(error),
+            Self::Connection(error) => Some(error),
+            Self::Read(error) => Some(error),
+            Self::ApplicationClosed(..) | Self::Closed | Self::TooLarge => None,
+        }
+    }
+}
+
+impl RecvError {
+    /// Returns the close reason of this [`RecvError`].
+    ///
+    /// Present when the connection was closed with a reason or timed out.
+    #[must_use]
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        match self {
+            Self::ApplicationClosed(reason, _) => Some(*reason),
+            Self::Connection(quinn::ConnectionError::TimedOut) => Some(CloseReason::IdleTimeout),
+            _ => None,
+        }
+    }
 }
 
 /// Send Error.
 #[derive(Debug)]
 pub enum SendError {
+    /// Closed.
+    ///
+    /// Connection was closed by the transport.
+    Closed,
+
+    /// Codec.
+    Codec(CodecError),
+
     /// Connection.
     Connection(quinn::ConnectionError),
 
-    /// Json.
-    Json(serde_json::Error),
+    /// Datagram.
+    Datagram(quinn::SendDatagramError),
+
+    /// Too Large.
+    ///
+    /// Message exceeds the maximum frame size.
+    TooLarge,
 
     /// Write.
     Write(quinn::WriteError),
 }
 
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "connection closed"),
+            Self::Codec(_) => write!(f, "unable to encode message"),
+            Self::Connection(_) => write!(f, "unable to open stream"),
+            Self::Datagram(_) => write!(f, "unable to send datagram"),
+            Self::TooLarge => write!(f, "message exceeds the maximum frame size"),
+            Self::Write(_) => write!(f, "unable to write frame"),
+        }
+    }
+}
+
+impl std::error::Error for SendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Codec(error) => Some(error),
+            Self::Connection(error) => Some(error),
+            Self::Datagram(error) => Some(error),
+            Self::Write(error) => Some(error),
+            Self::Closed | Self::TooLarge => None,
+        }
+    }
+}
+
 /// Server.
 #[derive(Debug, Clone)]
 pub struct Server {
-    inner: quinn::Endpoint,
+    conditions: Option<NetworkConditions>,
+    inner: ServerInner,
+    limits: Limits,
+}
+
+/// Server Inner.
+#[derive(Debug, Clone)]
+enum ServerInner {
+    Memory(Arc<memory::MemoryListener>),
+    Quic(quinn::Endpoint),
 }
 
 impl Server {
@@ -210,75 +580,80 @@ impl Server {
     /// # Errors
     ///
     /// Will return `Err` if unable to bind to port or find certificate.
+    pub fn new(config: &ServerConfig) -> Result<Self, io::Error> {
+        let inner = match config.transport() {
+            TransportConfig::Memory(network) => {
+                ServerInner::Memory(Arc::new(network.bind(config)?))
+            }
+            TransportConfig::Quic => ServerInner::Quic(quic::server(config)?),
+        };
+        Ok(Self {
+            conditions: config.conditions(),
+            inner,
+            limits: config.limits(),
+        })
+    }
+
+    /// Returns the local address of this [`Server`].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Will panic if unable to parse socket address.
-    pub fn new() -> Result<Self, io::Error> {
-        let config = Self::config()?;
-        let inner = quinn::Endpoint::server(config, "[::1]:4433".parse().unwrap())?;
-        Ok(Self { inner })
+    /// Will return `Err` if unable to query socket.
+    pub fn local_address(&self) -> Result<SocketAddr, io::Error> {
+        match &self.inner {
+            ServerInner::Memory(listener) => Ok(listener.local_address()),
+            ServerInner::Quic(endpoint) => endpoint.local_addr(),
+        }
     }
 
     /// Accept.
     pub async fn accept(&self) -> Option<Connecting> {
-        let inner = self.inner.accept().await?;
-        Some(Connecting { inner })
-    }
-
-    fn config() -> Result<quinn::ServerConfig, io::Error> {
-        let dirs = directories::ProjectDirs::from("uk.co", "agabani", "chaos-symphony").unwrap();
-        let path = dirs.data_local_dir();
-        let cert_path = path.join("cert.der");
-        let key_path = path.join("key.der");
-
-        let (cert, key) = match fs::read(&cert_path)
-            .and_then(|cert| Ok((cert, fs::read(&key_path)?)))
-        {
-            Ok(value) => value,
-            Err(error) if error.kind() == io::ErrorKind::NotFound => {
-                let cert = rcgen::generate_simple_self_signed(["localhost".to_string()]).unwrap();
-                let key = cert.serialize_private_key_der();
-                let cert = cert.serialize_der().unwrap();
-                fs::create_dir_all(path)?;
-                fs::write(&cert_path, &cert)?;
-                fs::write(&key_path, &key)?;
-                (cert, key)
+        let connecting = match &self.inner {
+            ServerInner::Memory(listener) => {
+                let connection = listener.accept().await?;
+                Connecting::new(connection.remote_address(), async move { Ok(connection) })
             }
-            Err(error) => {
-                return Err(error);
+            ServerInner::Quic(endpoint) => {
+                let connecting = endpoint.accept().await?;
+                quic::accept(connecting, self.limits)
             }
         };
-
-        let cert = rustls::Certificate(cert);
-        let key = rustls::PrivateKey(key);
-
-        let mut config = rustls::ServerConfig::builder()
-            .with_safe_defaults()
-            .with_no_client_auth()
-            .with_single_cert(vec![cert], key)
-            .unwrap();
-        config.alpn_protocols = vec![b"hq-29".into()];
-
-        let mut config = quinn::ServerConfig::with_crypto(Arc::new(config));
-        let transport_config = Arc::get_mut(&mut config.transport).unwrap();
-        transport_config.max_concurrent_uni_streams(0_u8.into());
-
-        Ok(config)
+        Some(connecting.with_conditions(self.conditions))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::mpsc;
+    use std::{
+        sync::mpsc,
+        time::{Duration, Instant},
+    };
 
-    use crate::{Client, Message, Server};
+    use crate::{
+        CertificateSource, Channel, Client, ClientAuthentication, ClientConfig, CloseReason,
+        ConnectError, Encoding, Limits, MemoryNetwork, Message, NetworkConditions, RecvError,
+        RootCertificateSource, SendError, Server, ServerConfig, TransportConfig,
+    };
 
     #[tokio::test]
     async fn test_connection() {
         // Arrange
-        let server = Server::new().unwrap();
-        let client = Client::new().unwrap();
+        let certificate = CertificateSource::generate(vec!["localhost".to_string()]).unwrap();
+        let (certificate_chain, _) = certificate.load().unwrap();
+        let root_certificates = RootCertificateSource::Memory(certificate_chain);
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_certificate(certificate)
+                .with_listen_address("[::1]:0".parse().unwrap()),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_root_certificates(root_certificates),
+        )
+        .unwrap();
 
         tokio::spawn(async move {
             println!("server: listening");
@@ -318,20 +693,20 @@ mod tests {
         let message_1 = Message {
             id: "1".to_string(),
             endpoint: "/1".to_string(),
-            header: "header 1".to_string(),
-            payload: "payload 1".to_string(),
+            header: b"header 1".to_vec(),
+            payload: b"payload 1".to_vec(),
         };
         let message_2 = Message {
             id: "2".to_string(),
             endpoint: "/2".to_string(),
-            header: "header 2".to_string(),
-            payload: "payload 2".to_string(),
+            header: b"header 2".to_vec(),
+            payload: b"payload 2".to_vec(),
         };
         let message_3 = Message {
             id: "3".to_string(),
             endpoint: "/3".to_string(),
-            header: "header 3".to_string(),
-            payload: "payload 3".to_string(),
+            header: b"header 3".to_vec(),
+            payload: b"payload 3".to_vec(),
         };
 
         // Act
@@ -340,6 +715,7 @@ mod tests {
         connection.send(message_3.clone()).await.unwrap();
 
         // Assert
+        assert_eq!(connection.encoding(), Encoding::Binary);
         tokio::task::spawn_blocking(move || {
             assert_eq!(message_1, recv.recv().unwrap());
             assert_eq!(message_2, recv.recv().unwrap());
@@ -348,4 +724,513 @@ mod tests {
         .await
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_client_authentication() {
+        // Arrange
+        let server_certificate =
+            CertificateSource::generate(vec!["localhost".to_string()]).unwrap();
+        let (server_certificate_chain, _) = server_certificate.load().unwrap();
+
+        let mut params = rcgen::CertificateParams::new(Vec::new());
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "simulation:test");
+        let client_certificate = rcgen::Certificate::from_params(params).unwrap();
+        let client_certificate_chain = vec![rustls::Certificate(
+            client_certificate.serialize_der().unwrap(),
+        )];
+        let client_certificate = CertificateSource::Memory {
+            certificate_chain: client_certificate_chain.clone(),
+            private_key: rustls::PrivateKey(client_certificate.serialize_private_key_der()),
+        };
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_certificate(server_certificate)
+                .with_client_authentication(ClientAuthentication::Required(
+                    RootCertificateSource::Memory(client_certificate_chain),
+                ))
+                .with_listen_address("[::1]:0".parse().unwrap()),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_certificate(client_certificate)
+                .with_remote_address(server.local_address().unwrap())
+                .with_root_certificates(RootCertificateSource::Memory(server_certificate_chain)),
+        )
+        .unwrap();
+
+        let server = tokio::spawn(async move {
+            let connecting = server.accept().await.unwrap();
+            connecting.accept().await.unwrap()
+        });
+
+        // Act
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.await.unwrap();
+
+        // Assert
+        assert!(connection.peer_certificate().is_some());
+
+        let peer_certificate = server_connection.peer_certificate().unwrap();
+        assert_eq!(peer_certificate.common_name(), Some("simulation:test"));
+        assert!(peer_certificate.matches("simulation:test"));
+        assert!(!peer_certificate.matches("client:test"));
+    }
+
+    #[tokio::test]
+    async fn test_send_unreliable() {
+        // Arrange
+        let certificate = CertificateSource::generate(vec!["localhost".to_string()]).unwrap();
+        let (certificate_chain, _) = certificate.load().unwrap();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_certificate(certificate)
+                .with_listen_address("[::1]:0".parse().unwrap()),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_root_certificates(RootCertificateSource::Memory(certificate_chain)),
+        )
+        .unwrap();
+
+        let server = tokio::spawn(async move {
+            let connecting = server.accept().await.unwrap();
+            connecting.accept().await.unwrap()
+        });
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.await.unwrap();
+
+        let datagram = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: b"payload 1".to_vec(),
+        };
+        let oversized = Message {
+            id: "2".to_string(),
+            endpoint: "/2".to_string(),
+            header: Vec::new(),
+            payload: vec![0; 64 * 1024],
+        };
+
+        // Act
+        connection
+            .send_unreliable(Channel::REPLICATION, datagram.clone())
+            .await
+            .unwrap();
+        connection
+            .send_unreliable(Channel::REPLICATION, oversized.clone())
+            .await
+            .unwrap();
+
+        // Assert
+        let mut received = vec![
+            server_connection.recv().await.unwrap(),
+            server_connection.recv().await.unwrap(),
+        ];
+        received.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(received, vec![datagram, oversized]);
+    }
+
+    #[tokio::test]
+    async fn test_recv_too_large() {
+        // Arrange
+        let certificate = CertificateSource::generate(vec!["localhost".to_string()]).unwrap();
+        let (certificate_chain, _) = certificate.load().unwrap();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_certificate(certificate)
+                .with_limits(Limits::default().with_max_frame_size(64))
+                .with_listen_address("[::1]:0".parse().unwrap()),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_root_certificates(RootCertificateSource::Memory(certificate_chain)),
+        )
+        .unwrap();
+
+        let server = tokio::spawn(async move {
+            let connecting = server.accept().await.unwrap();
+            connecting.accept().await.unwrap()
+        });
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.await.unwrap();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: vec![0; 128],
+        };
+
+        // Act
+        connection.send(message).await.unwrap();
+
+        // Assert
+        assert!(matches!(
+            server_connection.recv().await,
+            Err(RecvError::TooLarge)
+        ));
+        assert!(matches!(
+            connection.recv().await,
+            Err(RecvError::ApplicationClosed(
+                CloseReason::ProtocolViolation,
+                _
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_close() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        // Act
+        server_connection.send(message.clone()).await.unwrap();
+        server_connection.close(CloseReason::Kicked, "kicked").await;
+
+        // Assert
+        assert_eq!(connection.recv().await.unwrap(), message);
+        assert!(matches!(
+            connection.recv().await,
+            Err(RecvError::ApplicationClosed(CloseReason::Kicked, reason)) if reason == "kicked"
+        ));
+        assert_eq!(connection.close_reason(), Some(CloseReason::Kicked));
+        assert!(matches!(
+            server_connection.send(message).await,
+            Err(SendError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memory_connection() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_encodings(&[Encoding::Json])
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: br#""header 1""#.to_vec(),
+            payload: br#"{"payload":1}"#.to_vec(),
+        };
+
+        // Act
+        connection.send(message.clone()).await.unwrap();
+        drop(connection);
+
+        // Assert
+        assert_eq!(server_connection.encoding(), Encoding::Json);
+        assert_eq!(server_connection.recv().await.unwrap(), message);
+        assert!(matches!(
+            server_connection.recv().await,
+            Err(RecvError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memory_client_authentication() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let client_certificate =
+            CertificateSource::generate(vec!["simulation:test".to_string()]).unwrap();
+        let (client_certificate_chain, _) = client_certificate.load().unwrap();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_client_authentication(ClientAuthentication::Required(
+                    RootCertificateSource::Memory(client_certificate_chain),
+                ))
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let anonymous = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_certificate(client_certificate)
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        // Act
+        let refused = anonymous.connect();
+        client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        // Assert
+        assert!(matches!(refused, Err(ConnectError::Refused)));
+
+        let peer_certificate = server_connection.peer_certificate().unwrap();
+        assert!(peer_certificate.matches("simulation:test"));
+    }
+
+    #[test]
+    fn test_json_embeds_header_and_payload() {
+        // Arrange
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: br#"{"sequence":1}"#.to_vec(),
+            payload: br#"{"payload":1}"#.to_vec(),
+        };
+
+        // Act
+        let buf = Encoding::Json.encode(&message).unwrap();
+        let decoded: Message = Encoding::Json.decode(&buf).unwrap();
+
+        // Assert
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            r#"{"id":"1","endpoint":"/1","header":{"sequence":1},"payload":{"payload":1}}"#
+        );
+        assert_eq!(decoded, message);
+    }
+
+    #[tokio::test]
+    async fn test_connect_to() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let server_config =
+            ServerConfig::default().with_transport(TransportConfig::Memory(network.clone()));
+        let primary = Server::new(
+            &server_config
+                .clone()
+                .with_listen_address("[::1]:0".parse().unwrap()),
+        )
+        .unwrap();
+        let secondary =
+            Server::new(&server_config.with_listen_address("[::1]:0".parse().unwrap())).unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(primary.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        // Act
+        let connection = client
+            .connect_to(secondary.local_address().unwrap(), "localhost")
+            .unwrap()
+            .accept()
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            connection.remote_address(),
+            secondary.local_address().unwrap()
+        );
+        assert!(secondary.accept().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_network_conditions() {
+        // Arrange
+        let network = MemoryNetwork::new();
+        let latency = Duration::from_millis(20);
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_conditions(
+                    NetworkConditions::default()
+                        .with_latency(latency)
+                        .with_loss(1.0)
+                        .with_seed(0),
+                )
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        let unreliable = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+        let reliable = Message {
+            id: "2".to_string(),
+            endpoint: "/2".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        // Act
+        let sent = Instant::now();
+        connection
+            .send_unreliable(Channel::REPLICATION, unreliable)
+            .await
+            .unwrap();
+        connection.send(reliable.clone()).await.unwrap();
+
+        // Assert
+        assert_eq!(server_connection.recv().await.unwrap(), reliable);
+        assert!(sent.elapsed() >= latency);
+        assert!(tokio::time::timeout(latency * 4, server_connection.recv())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_network_conditions_closed() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_conditions(
+                    NetworkConditions::default()
+                        .with_latency(Duration::from_millis(1))
+                        .with_seed(0),
+                )
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        // Act
+        drop(server_connection);
+        let mut results = Vec::new();
+        for _ in 0..100 {
+            let result = connection.send(message.clone()).await;
+            let is_err = result.is_err();
+            results.push(result);
+            if is_err {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // Assert
+        assert!(matches!(results.last(), Some(Err(SendError::Closed))));
+        assert!(matches!(
+            connection.send(message).await,
+            Err(SendError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        // Arrange
+        let network = MemoryNetwork::new();
+
+        let server = Server::new(
+            &ServerConfig::default()
+                .with_listen_address("[::1]:0".parse().unwrap())
+                .with_transport(TransportConfig::Memory(network.clone())),
+        )
+        .unwrap();
+        let client = Client::new(
+            ClientConfig::default()
+                .with_remote_address(server.local_address().unwrap())
+                .with_transport(TransportConfig::Memory(network)),
+        )
+        .unwrap();
+
+        let connection = client.connect().unwrap().accept().await.unwrap();
+        let server_connection = server.accept().await.unwrap().accept().await.unwrap();
+
+        let message = Message {
+            id: "1".to_string(),
+            endpoint: "/1".to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        // Act
+        connection.send(message.clone()).await.unwrap();
+        connection.send(message).await.unwrap();
+        server_connection.recv().await.unwrap();
+
+        // Assert
+        assert_eq!(connection.stats().messages_sent.get("/1"), Some(&2));
+        assert_eq!(
+            server_connection.stats().messages_received.get("/1"),
+            Some(&1)
+        );
+    }
 }
@@ -0,0 +1,348 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv6Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    BoxFuture, CertificateSource, Channel, ClientAuthentication, ClientConfig, CloseReason,
+    ConnectError, Connecting, Connection, Encoding, Limits, PeerCertificate, RecvError,
+    Reliability, SendError, ServerConfig, Transport,
+};
+
+/// First port allocated to endpoints listening on port zero.
+const EPHEMERAL_PORT: u16 = 49152;
+
+/// Memory Network.
+///
+/// In-process network connecting clients and servers configured with the same
+/// [`MemoryNetwork`] using channels instead of sockets.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default)]
+pub struct MemoryNetwork {
+    inner: Arc<Mutex<MemoryNetworkState>>,
+}
+
+impl MemoryNetwork {
+    /// Creates a new [`MemoryNetwork`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds a listener to the listen address of the config.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn bind(&self, config: &ServerConfig) -> Result<MemoryListener, io::Error> {
+        let mut state = self.inner.lock().expect("poisoned");
+
+        let mut local_address = config.listen_address();
+        if local_address.port() == 0 {
+            local_address.set_port(state.allocate_port());
+        }
+
+        if state
+            .listeners
+            .get(&local_address)
+            .is_some_and(|listener| !listener.sender.is_closed())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{local_address} is in use"),
+            ));
+        }
+
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        state.listeners.insert(
+            local_address,
+            MemoryNetworkListener {
+                alpn_protocols: config.alpn_protocols().to_vec(),
+                client_authentication: config.client_authentication().clone(),
+                limits: config.limits(),
+                sender,
+            },
+        );
+
+        Ok(MemoryListener {
+            local_address,
+            receiver: tokio::sync::Mutex::new(receiver),
+        })
+    }
+
+    /// Connects to the remote address.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    pub(crate) fn connect(
+        &self,
+        config: &ClientConfig,
+        remote_address: SocketAddr,
+    ) -> Result<Connecting, ConnectError> {
+        let mut state = self.inner.lock().expect("poisoned");
+
+        let mut local_address = config.listen_address();
+        if local_address.ip().is_unspecified() {
+            local_address.set_ip(Ipv6Addr::LOCALHOST.into());
+        }
+        if local_address.port() == 0 {
+            local_address.set_port(state.allocate_port());
+        }
+
+        let client_id = state.allocate_id();
+        let server_id = state.allocate_id();
+
+        let listener = state
+            .listeners
+            .get(&remote_address)
+            .ok_or(ConnectError::Refused)?;
+
+        // the server selects its most preferred protocol offered by the client.
+        let encoding = Encoding::from_alpn_protocol(
+            listener
+                .alpn_protocols
+                .iter()
+                .find(|protocol| config.alpn_protocols().contains(protocol))
+                .map(Vec::as_slice),
+        );
+
+        let peer_certificate =
+            present_certificate(&listener.client_authentication, config.certificate())?;
+
+        let (client_sender, server_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let (server_sender, client_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let server_transport = MemoryTransport {
+            closed: Mutex::default(),
+            closing: tokio::sync::Notify::new(),
+            id: server_id,
+            max_frame_size: listener.limits.max_frame_size(),
+            receiver: tokio::sync::Mutex::new(server_receiver),
+            remote_address: local_address,
+            sender: server_sender,
+        };
+        listener
+            .sender
+            .send(Connection::new(
+                Arc::new(server_transport),
+                encoding,
+                listener.limits,
+                peer_certificate,
+            ))
+            .map_err(|_| ConnectError::Refused)?;
+
+        let client_transport = MemoryTransport {
+            closed: Mutex::default(),
+            closing: tokio::sync::Notify::new(),
+            id: client_id,
+            max_frame_size: config.limits().max_frame_size(),
+            receiver: tokio::sync::Mutex::new(client_receiver),
+            remote_address,
+            sender: client_sender,
+        };
+        let connection =
+            Connection::new(Arc::new(client_transport), encoding, config.limits(), None);
+
+        Ok(Connecting::new(
+            remote_address,
+            async move { Ok(connection) },
+        ))
+    }
+}
+
+/// Presents the client certificate to a server requesting client
+/// authentication.
+///
+/// The certificate is not verified against the root certificates in memory.
+fn present_certificate(
+    client_authentication: &ClientAuthentication,
+    certificate: Option<&CertificateSource>,
+) -> Result<Option<PeerCertificate>, ConnectError> {
+    match (client_authentication, certificate) {
+        (ClientAuthentication::None, _) | (ClientAuthentication::Optional(_), None) => Ok(None),
+        (ClientAuthentication::Required(_), None) => Err(ConnectError::Refused),
+        (_, Some(certificate)) => {
+            let (certificate_chain, _) = certificate.load().map_err(|_| ConnectError::Refused)?;
+            certificate_chain
+                .first()
+                .map(PeerCertificate::parse)
+                .transpose()
+                .map_err(|_| ConnectError::Refused)
+        }
+    }
+}
+
+/// Memory Network State.
+#[derive(Debug, Default)]
+struct MemoryNetworkState {
+    listeners: HashMap<SocketAddr, MemoryNetworkListener>,
+    next_id: usize,
+    next_port: u16,
+}
+
+impl MemoryNetworkState {
+    /// Allocates a connection id.
+    fn allocate_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Allocates an ephemeral port.
+    fn allocate_port(&mut self) -> u16 {
+        let port = EPHEMERAL_PORT + self.next_port;
+        self.next_port = (self.next_port + 1) % (u16::MAX - EPHEMERAL_PORT);
+        port
+    }
+}
+
+/// Memory Network Listener.
+#[derive(Debug)]
+struct MemoryNetworkListener {
+    alpn_protocols: Vec<Vec<u8>>,
+    client_authentication: ClientAuthentication,
+    limits: Limits,
+    sender: tokio::sync::mpsc::UnboundedSender<Connection>,
+}
+
+/// Memory Listener.
+///
+/// Server end of a [`MemoryNetwork`].
+#[derive(Debug)]
+pub(crate) struct MemoryListener {
+    local_address: SocketAddr,
+    receiver: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<Connection>>,
+}
+
+impl MemoryListener {
+    /// Accept.
+    pub(crate) async fn accept(&self) -> Option<Connection> {
+        self.receiver.lock().await.recv().await
+    }
+
+    /// Returns the local address of this [`MemoryListener`].
+    pub(crate) fn local_address(&self) -> SocketAddr {
+        self.local_address
+    }
+}
+
+/// Memory Frame.
+#[derive(Debug)]
+enum MemoryFrame {
+    /// Close.
+    Close(CloseReason, String),
+
+    /// Data.
+    Data(Vec<u8>),
+}
+
+/// Memory Closed.
+#[derive(Debug)]
+enum MemoryClosed {
+    /// Closed by this end.
+    Locally,
+
+    /// Closed by the remote end.
+    Remotely(CloseReason, String),
+}
+
+/// Memory Transport.
+///
+/// Frames are delivered reliably and in order regardless of [`Channel`] and
+/// [`Reliability`].
+#[derive(Debug)]
+struct MemoryTransport {
+    closed: Mutex<Option<MemoryClosed>>,
+    closing: tokio::sync::Notify,
+    id: usize,
+    max_frame_size: usize,
+    receiver: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<MemoryFrame>>,
+    remote_address: SocketAddr,
+    sender: tokio::sync::mpsc::UnboundedSender<MemoryFrame>,
+}
+
+impl MemoryTransport {
+    /// Returns the error of this [`MemoryTransport`] once closed.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    fn closed_error(&self) -> Option<RecvError> {
+        match &*self.closed.lock().expect("poisoned") {
+            Some(MemoryClosed::Locally) => Some(RecvError::Closed),
+            Some(MemoryClosed::Remotely(reason, message)) => {
+                Some(RecvError::ApplicationClosed(*reason, message.clone()))
+            }
+            None => None,
+        }
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn close<'a>(&'a self, reason: CloseReason, message: &'a str) -> BoxFuture<'a, ()> {
+        let mut closed = self.closed.lock().expect("poisoned");
+        if closed.is_none() {
+            *closed = Some(MemoryClosed::Locally);
+            let _ = self
+                .sender
+                .send(MemoryFrame::Close(reason, message.to_string()));
+            self.closing.notify_one();
+        }
+        Box::pin(async {})
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Result<Vec<u8>, RecvError>> {
+        Box::pin(async move {
+            if let Some(error) = self.closed_error() {
+                return Err(error);
+            }
+
+            let mut receiver = self.receiver.lock().await;
+            let frame = tokio::select! {
+                frame = receiver.recv() => frame,
+                () = self.closing.notified() => return Err(RecvError::Closed),
+            };
+
+            match frame {
+                Some(MemoryFrame::Close(reason, message)) => {
+                    let mut closed = self.closed.lock().expect("poisoned");
+                    if closed.is_none() {
+                        *closed = Some(MemoryClosed::Remotely(reason, message.clone()));
+                    }
+                    Err(RecvError::ApplicationClosed(reason, message))
+                }
+                Some(MemoryFrame::Data(buf)) if buf.len() > self.max_frame_size => {
+                    Err(RecvError::TooLarge)
+                }
+                Some(MemoryFrame::Data(buf)) => Ok(buf),
+                None => Err(RecvError::Closed),
+            }
+        })
+    }
+
+    fn remote_address(&self) -> SocketAddr {
+        self.remote_address
+    }
+
+    fn send(
+        &self,
+        _: Channel,
+        _: Reliability,
+        buf: Vec<u8>,
+    ) -> BoxFuture<'_, Result<(), SendError>> {
+        if self.closed.lock().expect("poisoned").is_some() {
+            return Box::pin(async { Err(SendError::Closed) });
+        }
+        let result = self
+            .sender
+            .send(MemoryFrame::Data(buf))
+            .map_err(|_| SendError::Closed);
+        Box::pin(async { result })
+    }
+}
@@ -0,0 +1,447 @@
+use std::{collections::HashMap, io, net::SocketAddr, sync::Arc, time::Duration};
+
+use crate::{
+    BoxFuture, Channel, ClientAuthentication, ClientConfig, CloseReason, ConnectError, Connecting,
+    Connection, ConnectionStats, Encoding, Limits, PeerCertificate, RecvError, Reliability,
+    SendError, ServerConfig, Transport,
+};
+
+/// Number of received frames buffered before applying back pressure.
+const RECV_BUFFER: usize = 1024;
+
+/// Maximum time waited for sent frames to be acknowledged when closing.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Creates a QUIC client endpoint.
+pub(crate) fn client(config: &ClientConfig) -> Result<quinn::Endpoint, io::Error> {
+    let roots = config.root_certificates().load()?;
+
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+
+    let mut crypto = match config.certificate() {
+        Some(certificate) => {
+            let (certificate_chain, private_key) = certificate.load()?;
+            builder
+                .with_client_auth_cert(certificate_chain, private_key)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    crypto.alpn_protocols = config.alpn_protocols().to_vec();
+
+    let mut client_config = quinn::ClientConfig::new(Arc::new(crypto));
+    client_config.transport_config(Arc::new(transport_config(config.limits())));
+
+    let mut endpoint = quinn::Endpoint::client(config.listen_address())?;
+    endpoint.set_default_client_config(client_config);
+    Ok(endpoint)
+}
+
+/// Creates a QUIC server endpoint.
+pub(crate) fn server(config: &ServerConfig) -> Result<quinn::Endpoint, io::Error> {
+    let (certificate_chain, private_key) = config.certificate().load()?;
+
+    let builder = rustls::ServerConfig::builder().with_safe_defaults();
+
+    let builder = match config.client_authentication() {
+        ClientAuthentication::None => {
+            builder.with_client_cert_verifier(rustls::server::NoClientAuth::boxed())
+        }
+        ClientAuthentication::Optional(roots) => builder.with_client_cert_verifier(
+            rustls::server::AllowAnyAnonymousOrAuthenticatedClient::new(roots.load()?).boxed(),
+        ),
+        ClientAuthentication::Required(roots) => builder.with_client_cert_verifier(
+            rustls::server::AllowAnyAuthenticatedClient::new(roots.load()?).boxed(),
+        ),
+    };
+
+    let mut crypto = builder
+        .with_single_cert(certificate_chain, private_key)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    crypto.alpn_protocols = config.alpn_protocols().to_vec();
+
+    let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    server_config.transport_config(Arc::new(transport_config(config.limits())));
+
+    quinn::Endpoint::server(server_config, config.listen_address())
+}
+
+/// Connects to the remote address.
+pub(crate) fn connect(
+    endpoint: &quinn::Endpoint,
+    config: &ClientConfig,
+    remote_address: SocketAddr,
+    server_name: &str,
+) -> Result<Connecting, ConnectError> {
+    let connecting = endpoint
+        .connect(remote_address, server_name)
+        .map_err(ConnectError::Connect)?;
+    Ok(accept(connecting, config.limits()))
+}
+
+/// Accepts a connection once the handshake completes.
+pub(crate) fn accept(connecting: quinn::Connecting, limits: Limits) -> Connecting {
+    let remote_address = connecting.remote_address();
+    Connecting::new(remote_address, async move {
+        let inner = connecting.await.map_err(crate::AcceptError::Connection)?;
+
+        let peer_certificate = inner
+            .peer_identity()
+            .and_then(|identity| identity.downcast::<Vec<rustls::Certificate>>().ok())
+            .and_then(|certificate_chain| {
+                certificate_chain
+                    .first()
+                    .and_then(|certificate| PeerCertificate::parse(certificate).ok())
+            });
+
+        let encoding = Encoding::from_alpn_protocol(
+            inner
+                .handshake_data()
+                .and_then(|handshake_data| {
+                    handshake_data
+                        .downcast::<quinn::crypto::rustls::HandshakeData>()
+                        .ok()
+                })
+                .and_then(|handshake_data| handshake_data.protocol)
+                .as_deref(),
+        );
+
+        let receiver = spawn_recv(inner.clone(), limits.max_frame_size());
+
+        let transport = QuicTransport {
+            inner,
+            receiver: tokio::sync::Mutex::new(receiver),
+            send_streams: SendStreams::default(),
+        };
+
+        Ok(Connection::new(
+            Arc::new(transport),
+            encoding,
+            limits,
+            peer_certificate,
+        ))
+    })
+}
+
+/// Transport Config.
+///
+/// Allows a unidirectional stream per [`Channel`] up to the limit.
+fn transport_config(limits: Limits) -> quinn::TransportConfig {
+    let mut transport_config = quinn::TransportConfig::default();
+    transport_config
+        .max_concurrent_bidi_streams(0_u8.into())
+        .max_concurrent_uni_streams(limits.max_concurrent_uni_streams().into());
+    transport_config
+}
+
+/// QUIC Transport.
+///
+/// Sends reliable frames on a long-lived unidirectional stream per [`Channel`]
+/// and unreliable frames as datagrams.
+#[derive(Debug)]
+struct QuicTransport {
+    inner: quinn::Connection,
+    receiver: tokio::sync::Mutex<tokio::sync::mpsc::Receiver<Result<Vec<u8>, RecvError>>>,
+    send_streams: SendStreams,
+}
+
+impl Transport for QuicTransport {
+    fn close<'a>(&'a self, reason: CloseReason, message: &'a str) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let _ = tokio::time::timeout(CLOSE_TIMEOUT, self.send_streams.finish()).await;
+            close(&self.inner, reason, message);
+        })
+    }
+
+    fn id(&self) -> usize {
+        self.inner.stable_id()
+    }
+
+    fn recv(&self) -> BoxFuture<'_, Result<Vec<u8>, RecvError>> {
+        Box::pin(async move {
+            match self.receiver.lock().await.recv().await {
+                Some(result) => result,
+                None => Err(connection_error(
+                    self.inner
+                        .close_reason()
+                        .unwrap_or(quinn::ConnectionError::LocallyClosed),
+                )),
+            }
+        })
+    }
+
+    fn remote_address(&self) -> SocketAddr {
+        self.inner.remote_address()
+    }
+
+    fn send(
+        &self,
+        channel: Channel,
+        reliability: Reliability,
+        buf: Vec<u8>,
+    ) -> BoxFuture<'_, Result<(), SendError>> {
+        Box::pin(async move {
+            if reliability == Reliability::Unreliable
+                && self
+                    .inner
+                    .max_datagram_size()
+                    .is_some_and(|max_datagram_size| buf.len() <= max_datagram_size)
+            {
+                return self
+                    .inner
+                    .send_datagram(buf.into())
+                    .map_err(SendError::Datagram);
+            }
+
+            self.send_streams.send(&self.inner, channel, &buf).await
+        })
+    }
+
+    fn stats(&self) -> ConnectionStats {
+        let stats = self.inner.stats();
+        ConnectionStats {
+            bytes_received: stats.udp_rx.bytes,
+            bytes_sent: stats.udp_tx.bytes,
+            congestion_events: stats.path.congestion_events,
+            congestion_window: stats.path.cwnd,
+            lost_packets: stats.path.lost_packets,
+            rtt: stats.path.rtt,
+            sent_packets: stats.path.sent_packets,
+            ..ConnectionStats::default()
+        }
+    }
+}
+
+/// Send Streams.
+///
+/// Lazily opened unidirectional stream per [`Channel`].
+#[derive(Debug, Default)]
+struct SendStreams {
+    inner: std::sync::Mutex<HashMap<Channel, Arc<tokio::sync::Mutex<Option<quinn::SendStream>>>>>,
+}
+
+impl SendStreams {
+    /// Finishes the open streams, waiting for their frames to be acknowledged.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    async fn finish(&self) {
+        let streams: Vec<_> = self
+            .inner
+            .lock()
+            .expect("poisoned")
+            .values()
+            .cloned()
+            .collect();
+
+        for stream in streams {
+            if let Some(mut send) = stream.lock().await.take() {
+                let _ = send.finish().await;
+            }
+        }
+    }
+
+    /// Writes a length delimited frame to the stream of the channel.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if [`Mutex`] is poisoned.
+    async fn send(
+        &self,
+        connection: &quinn::Connection,
+        channel: Channel,
+        buf: &[u8],
+    ) -> Result<(), SendError> {
+        let length = u32::try_from(buf.len()).map_err(|_| SendError::TooLarge)?;
+
+        let stream = self
+            .inner
+            .lock()
+            .expect("poisoned")
+            .entry(channel)
+            .or_default()
+            .clone();
+        let mut stream = stream.lock().await;
+
+        let send = match &mut *stream {
+            Some(send) => send,
+            None => stream.insert(connection.open_uni().await.map_err(SendError::Connection)?),
+        };
+
+        let result = async {
+            send.write_all(&length.to_be_bytes()).await?;
+            send.write_all(buf).await
+        }
+        .await;
+
+        if result.is_err() {
+            // the stream is unusable, a new stream is opened on the next send.
+            *stream = None;
+        }
+
+        result.map_err(SendError::Write)
+    }
+}
+
+/// Spawns tasks accepting streams and datagrams and reading their frames.
+fn spawn_recv(
+    connection: quinn::Connection,
+    max_frame_size: usize,
+) -> tokio::sync::mpsc::Receiver<Result<Vec<u8>, RecvError>> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(RECV_BUFFER);
+
+    tokio::spawn(recv_datagrams(
+        connection.clone(),
+        max_frame_size,
+        sender.clone(),
+    ));
+
+    tokio::spawn(async move {
+        loop {
+            let result = tokio::select! {
+                result = connection.accept_uni() => result,
+                () = sender.closed() => return,
+            };
+
+            match result {
+                Ok(recv) => {
+                    tokio::spawn(recv_frames(
+                        connection.clone(),
+                        recv,
+                        max_frame_size,
+                        sender.clone(),
+                    ));
+                }
+                Err(error) => {
+                    let _ = sender.send(Err(connection_error(error))).await;
+                    return;
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Reads datagrams until the connection is lost.
+///
+/// Connection errors are reported by the stream acceptor.
+async fn recv_datagrams(
+    connection: quinn::Connection,
+    max_frame_size: usize,
+    sender: tokio::sync::mpsc::Sender<Result<Vec<u8>, RecvError>>,
+) {
+    loop {
+        let result = tokio::select! {
+            result = connection.read_datagram() => result,
+            () = sender.closed() => return,
+        };
+
+        let Ok(buf) = result else {
+            return;
+        };
+
+        if buf.len() > max_frame_size {
+            close(
+                &connection,
+                CloseReason::ProtocolViolation,
+                "frame too large",
+            );
+            let _ = sender.send(Err(RecvError::TooLarge)).await;
+            return;
+        }
+
+        if sender.send(Ok(buf.to_vec())).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads length delimited frames until the stream is finished.
+async fn recv_frames(
+    connection: quinn::Connection,
+    mut recv: quinn::RecvStream,
+    max_frame_size: usize,
+    sender: tokio::sync::mpsc::Sender<Result<Vec<u8>, RecvError>>,
+) {
+    loop {
+        let result = tokio::select! {
+            result = recv_frame(&mut recv, max_frame_size) => result,
+            () = sender.closed() => return,
+        };
+
+        let (result, is_finished) = match result {
+            Ok(Some(buf)) => (Ok(buf), false),
+            Ok(None) => return,
+            Err(RecvError::TooLarge) => {
+                close(
+                    &connection,
+                    CloseReason::ProtocolViolation,
+                    "frame too large",
+                );
+                (Err(RecvError::TooLarge), true)
+            }
+            Err(error) => (Err(error), true),
+        };
+
+        if sender.send(result).await.is_err() || is_finished {
+            return;
+        }
+    }
+}
+
+/// Reads a length delimited frame.
+///
+/// Returns `None` if the stream finished on a frame boundary.
+async fn recv_frame(
+    recv: &mut quinn::RecvStream,
+    max_frame_size: usize,
+) -> Result<Option<Vec<u8>>, RecvError> {
+    let mut length = [0; 4];
+    match recv.read_exact(&mut length).await {
+        Ok(()) => {}
+        Err(quinn::ReadExactError::FinishedEarly) => return Ok(None),
+        Err(error) => return Err(read_error(error)),
+    }
+
+    let length = u32::from_be_bytes(length) as usize;
+    if length > max_frame_size {
+        return Err(RecvError::TooLarge);
+    }
+
+    let mut buf = vec![0; length];
+    recv.read_exact(&mut buf).await.map_err(read_error)?;
+    Ok(Some(buf))
+}
+
+/// Closes the connection with the reason and message.
+fn close(connection: &quinn::Connection, reason: CloseReason, message: &str) {
+    connection.close(
+        quinn::VarInt::from_u64(reason.code()).unwrap_or(quinn::VarInt::MAX),
+        message.as_bytes(),
+    );
+}
+
+/// Converts a connection error, extracting the reason of application closes.
+fn connection_error(error: quinn::ConnectionError) -> RecvError {
+    match error {
+        quinn::ConnectionError::ApplicationClosed(close) => RecvError::ApplicationClosed(
+            CloseReason::from_code(close.error_code.into_inner()),
+            String::from_utf8_lossy(&close.reason).into_owned(),
+        ),
+        error => RecvError::Connection(error),
+    }
+}
+
+/// Converts a read error, extracting the reason of lost connections.
+fn read_error(error: quinn::ReadExactError) -> RecvError {
+    match error {
+        quinn::ReadExactError::ReadError(quinn::ReadError::ConnectionLost(error)) => {
+            connection_error(error)
+        }
+        error => RecvError::Read(error),
+    }
+}
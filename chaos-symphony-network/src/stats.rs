@@ -0,0 +1,80 @@
+use std::{collections::HashMap, time::Duration};
+
+/// Maximum number of distinct endpoints counted per direction.
+///
+/// Endpoints are chosen by the peer, further endpoints are counted under
+/// [`OTHER_ENDPOINT`].
+const MAX_ENDPOINTS: usize = 256;
+
+/// Endpoint counting messages beyond [`MAX_ENDPOINTS`].
+const OTHER_ENDPOINT: &str = "*";
+
+/// Connection Stats.
+///
+/// Snapshot of the link quality and traffic of a connection.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Bytes Received.
+    pub bytes_received: u64,
+
+    /// Bytes Sent.
+    pub bytes_sent: u64,
+
+    /// Congestion Events.
+    pub congestion_events: u64,
+
+    /// Congestion Window.
+    pub congestion_window: u64,
+
+    /// Lost Packets.
+    pub lost_packets: u64,
+
+    /// Messages Received per endpoint.
+    pub messages_received: HashMap<String, u64>,
+
+    /// Messages Sent per endpoint.
+    pub messages_sent: HashMap<String, u64>,
+
+    /// Round Trip Time.
+    pub rtt: Duration,
+
+    /// Sent Packets.
+    pub sent_packets: u64,
+}
+
+/// Message Counts.
+#[derive(Debug, Default)]
+pub(crate) struct MessageCounts {
+    received: HashMap<String, u64>,
+    sent: HashMap<String, u64>,
+}
+
+impl MessageCounts {
+    /// Counts a received message.
+    pub(crate) fn received(&mut self, endpoint: &str) {
+        increment(&mut self.received, endpoint);
+    }
+
+    /// Counts a sent message.
+    pub(crate) fn sent(&mut self, endpoint: &str) {
+        increment(&mut self.sent, endpoint);
+    }
+
+    /// Copies the counts into the stats.
+    pub(crate) fn apply(&self, stats: &mut ConnectionStats) {
+        stats.messages_received.clone_from(&self.received);
+        stats.messages_sent.clone_from(&self.sent);
+    }
+}
+
+/// Increments the count of the endpoint.
+fn increment(counts: &mut HashMap<String, u64>, endpoint: &str) {
+    if let Some(count) = counts.get_mut(endpoint) {
+        *count += 1;
+    } else if counts.len() < MAX_ENDPOINTS {
+        counts.insert(endpoint.to_string(), 1);
+    } else {
+        *counts.entry(OTHER_ENDPOINT.to_string()).or_default() += 1;
+    }
+}
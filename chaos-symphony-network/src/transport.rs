@@ -0,0 +1,51 @@
+use std::{fmt::Debug, future::Future, net::SocketAddr, pin::Pin};
+
+use crate::{Channel, CloseReason, ConnectionStats, RecvError, Reliability, SendError};
+
+/// Boxed Future.
+pub(crate) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Transport.
+///
+/// Moves encoded frames between the two ends of a [`Connection`](crate::Connection).
+pub(crate) trait Transport: Debug + Send + Sync {
+    /// Close.
+    ///
+    /// Delivers frames already sent, then closes the transport with the
+    /// reason and message.
+    fn close<'a>(&'a self, reason: CloseReason, message: &'a str) -> BoxFuture<'a, ()>;
+
+    /// Returns the id of this [`Transport`].
+    fn id(&self) -> usize;
+
+    /// Recv.
+    ///
+    /// Frames are received in order per [`Channel`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if connection is lost or unable to read.
+    fn recv(&self) -> BoxFuture<'_, Result<Vec<u8>, RecvError>>;
+
+    /// Returns the remote address of this [`Transport`].
+    fn remote_address(&self) -> SocketAddr;
+
+    /// Send.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if connection is lost or unable to write.
+    fn send(
+        &self,
+        channel: Channel,
+        reliability: Reliability,
+        buf: Vec<u8>,
+    ) -> BoxFuture<'_, Result<(), SendError>>;
+
+    /// Returns the stats of this [`Transport`].
+    ///
+    /// Transports without link statistics report zeroes.
+    fn stats(&self) -> ConnectionStats {
+        ConnectionStats::default()
+    }
+}
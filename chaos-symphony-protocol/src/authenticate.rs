@@ -1,3 +1,4 @@
+use bevy::utils::Uuid;
 use serde::{Deserialize, Serialize};
 
 use crate::{Identity, Message, MessageCallback, Request, Response};
@@ -32,6 +33,12 @@ impl Request<AuthenticateRequestPayload, AuthenticateResponse> for AuthenticateR
 pub struct AuthenticateRequestPayload {
     /// Identity.
     pub identity: Identity,
+
+    /// Session.
+    ///
+    /// Token of the session to resume, issued by a previous
+    /// [`AuthenticateResponsePayload::Success`].
+    pub session: Option<Uuid>,
 }
 
 /*
@@ -60,7 +67,17 @@ pub enum AuthenticateResponsePayload {
         /// Client Identity.
         client_identity: Identity,
 
+        /// Is Resumed.
+        ///
+        /// Whether the requested session was resumed.
+        is_resumed: bool,
+
         /// Server Identity.
         server_identity: Identity,
+
+        /// Session.
+        ///
+        /// Token to resume the session with after reconnecting.
+        session: Uuid,
     },
 }
@@ -0,0 +1,30 @@
+use bevy::utils::Uuid;
+use serde::{Deserialize, Serialize};
+
+use crate::{Event, Message};
+
+/*
+ * ============================================================================
+ * Event
+ * ============================================================================
+ */
+
+/// Cancel Event.
+///
+/// Sent when the [`MessageCallback`](crate::MessageCallback) or
+/// [`MessageStream`](crate::MessageStream) of a request is dropped or times out
+/// before the response is received.
+#[allow(clippy::module_name_repetitions)]
+pub type CancelEvent = Message<CancelEventPayload>;
+
+impl Event<CancelEventPayload> for CancelEvent {
+    const ENDPOINT: &'static str = "/event/cancel";
+}
+
+/// Cancel Event Payload.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CancelEventPayload {
+    /// Request Id.
+    pub request_id: Uuid,
+}
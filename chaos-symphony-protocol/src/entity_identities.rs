@@ -1,16 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{Message, MessageCallback, Request, Response};
+use crate::{Identity, Message, MessageStream, Response, StreamRequest};
 
 /*
  * ============================================================================
- * Callback
+ * Stream
  * ============================================================================
  */
 
-/// Entity Identities Callback.
+/// Entity Identities Stream.
 #[allow(clippy::module_name_repetitions)]
-pub type EntityIdentitiesCallback = MessageCallback<EntityIdentitiesResponse>;
+pub type EntityIdentitiesStream = MessageStream<EntityIdentitiesResponse>;
 
 /*
  * ============================================================================
@@ -22,8 +22,14 @@ pub type EntityIdentitiesCallback = MessageCallback<EntityIdentitiesResponse>;
 #[allow(clippy::module_name_repetitions)]
 pub type EntityIdentitiesRequest = Message<EntityIdentitiesRequestPayload>;
 
-impl Request<EntityIdentitiesRequestPayload, EntityIdentitiesResponse> for EntityIdentitiesRequest {
+impl StreamRequest<EntityIdentitiesRequestPayload, EntityIdentitiesResponse>
+    for EntityIdentitiesRequest
+{
     const ENDPOINT: &'static str = "/request/entity_identities";
+
+    const END_ENDPOINT: &'static str = "/response/entity_identities/end";
+
+    const NOTIFY_CANCEL: bool = true;
 }
 
 /// Identities Request Payload.
@@ -53,5 +59,12 @@ pub enum EntityIdentitiesResponsePayload {
     Failure,
 
     /// Success.
-    Success,
+    Success {
+        /// Entity Identities.
+        ///
+        /// A chunk of the entity identities known to the server. Once the
+        /// stream has ended, entities missing from every chunk, such as those
+        /// removed while disconnected, can be removed.
+        entity_identities: Vec<Identity>,
+    },
 }
@@ -4,21 +4,25 @@
 //! Chaos Symphony Protocol
 
 mod authenticate;
+mod cancel;
 mod entity_authority;
 mod entity_identities;
 mod entity_identity;
 mod message;
 mod ping;
 mod replicate_entity_components;
+mod stream_end;
 mod transformation;
 mod types;
 
 pub use authenticate::*;
+pub use cancel::*;
 pub use entity_authority::*;
 pub use entity_identities::*;
 pub use entity_identity::*;
 pub use message::*;
 pub use ping::*;
 pub use replicate_entity_components::*;
+pub use stream_end::*;
 pub use transformation::*;
 pub use types::*;
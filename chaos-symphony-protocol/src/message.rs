@@ -1,13 +1,18 @@
-use std::{convert::From, marker::PhantomData};
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
 
 use bevy::prelude::*;
 use bevy::utils::Uuid;
-use chaos_symphony_async::{Future, Poll, PollError};
-use chaos_symphony_network_bevy::{NetworkEndpoint, NetworkSend};
+use chaos_symphony_async::{Future, Poll, PollError, Stream};
+use chaos_symphony_network::{Channel, CodecError, Encoding, Reliability};
+use chaos_symphony_network_bevy::{NetworkEndpoint, TrySendError};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use tokio::sync::mpsc::error::SendError;
 
-use crate::Identity;
+use crate::{CancelEvent, CancelEventPayload, Identity, StreamEnd, StreamEndPayload};
 
 /*
  * ============================================================================
@@ -31,39 +36,37 @@ pub struct Message<T> {
     pub payload: T,
 }
 
-impl<T> From<chaos_symphony_network::Message> for Message<T>
+impl<T> Decode for Message<T>
 where
     T: DeserializeOwned,
 {
-    fn from(value: chaos_symphony_network::Message) -> Self {
-        let header = match serde_json::from_str(&value.header) {
-            Ok(payload) => payload,
-            Err(error) => panic!("{error}\n{value:?}"),
-        };
-        let payload = match serde_json::from_str(&value.payload) {
-            Ok(payload) => payload,
-            Err(error) => panic!("{error}\n{value:?}"),
-        };
-        Self {
-            id: value.id.parse().unwrap(),
+    fn decode(
+        value: chaos_symphony_network::Message,
+        encoding: Encoding,
+    ) -> Result<Self, DecodeError> {
+        Ok(Self {
+            id: value
+                .id
+                .parse()
+                .map_err(|_| DecodeError::InvalidId(value.id.clone()))?,
             endpoint: value.endpoint,
-            header,
-            payload,
-        }
+            header: encoding.decode(&value.header)?,
+            payload: encoding.decode(&value.payload)?,
+        })
     }
 }
 
-impl<T> From<Message<T>> for chaos_symphony_network::Message
+impl<T> Encode for Message<T>
 where
     T: Serialize,
 {
-    fn from(value: Message<T>) -> Self {
-        Self {
-            id: value.id.to_string(),
-            endpoint: value.endpoint,
-            header: serde_json::to_string(&value.header).unwrap(),
-            payload: serde_json::to_string(&value.payload).unwrap(),
-        }
+    fn encode(self, encoding: Encoding) -> Result<chaos_symphony_network::Message, CodecError> {
+        Ok(chaos_symphony_network::Message {
+            id: self.id.to_string(),
+            endpoint: self.endpoint,
+            header: encoding.encode(&self.header)?,
+            payload: encoding.encode(&self.payload)?,
+        })
     }
 }
 
@@ -71,6 +74,12 @@ where
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MessageHeader {
+    /// Sequence.
+    ///
+    /// Increases with every message created by the source process, so events
+    /// arriving out of order can be detected. Kept when relayed.
+    pub sequence: u64,
+
     /// Source Endpoint ID.
     pub source_endpoint_id: Option<usize>,
 
@@ -78,6 +87,75 @@ pub struct MessageHeader {
     pub source_identity: Option<Identity>,
 }
 
+impl MessageHeader {
+    /// Creates a new [`MessageHeader`] with the next sequence.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            sequence: next_sequence(),
+            source_endpoint_id: None,
+            source_identity: None,
+        }
+    }
+}
+
+impl Default for MessageHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Last sequence issued by this process.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the next sequence.
+///
+/// Never less than the current time in microseconds, so sequences keep
+/// increasing when the process restarts.
+fn next_sequence() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| {
+            u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+        });
+    let previous = SEQUENCE
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |previous| {
+            Some(now.max(previous + 1))
+        })
+        .unwrap_or_else(|previous| previous);
+    now.max(previous + 1)
+}
+
+/// Decode.
+pub trait Decode
+where
+    Self: Sized,
+{
+    /// Decodes from a [`chaos_symphony_network::Message`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if id is not a [`Uuid`], or unable to deserialize
+    /// header or payload.
+    fn decode(
+        value: chaos_symphony_network::Message,
+        encoding: Encoding,
+    ) -> Result<Self, DecodeError>;
+}
+
+/// Encode.
+pub trait Encode
+where
+    Self: Sized,
+{
+    /// Encodes into a [`chaos_symphony_network::Message`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to serialize header or payload.
+    fn encode(self, encoding: Encoding) -> Result<chaos_symphony_network::Message, CodecError>;
+}
+
 /// Message ID.
 #[allow(clippy::module_name_repetitions)]
 pub trait MessageId {
@@ -104,6 +182,8 @@ pub struct MessageCallback<T> {
     /// Id.
     pub id: Uuid,
 
+    encoding: Encoding,
+
     future: Future<chaos_symphony_network::Message>,
 
     marker: PhantomData<T>,
@@ -111,13 +191,18 @@ pub struct MessageCallback<T> {
 
 impl<T> MessageCallback<T>
 where
-    T: From<chaos_symphony_network::Message>,
+    T: Decode,
 {
     /// Creates a new [`MessageCallback`].
     #[must_use]
-    pub fn new(id: Uuid, future: Future<chaos_symphony_network::Message>) -> Self {
+    pub fn new(
+        id: Uuid,
+        encoding: Encoding,
+        future: Future<chaos_symphony_network::Message>,
+    ) -> Self {
         Self {
             id,
+            encoding,
             future,
             marker: PhantomData,
         }
@@ -130,8 +215,90 @@ where
     }
 
     /// Try poll.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected, timed out or
+    /// unable to decode response.
     pub fn try_poll(&self) -> Poll<Result<T, PollError>> {
-        self.future.try_poll().map(|result| result.map(Into::into))
+        self.future.try_poll().map(|result| {
+            result.and_then(|message| {
+                T::decode(message, self.encoding).map_err(|error| PollError::Decode(error.into()))
+            })
+        })
+    }
+}
+
+/*
+ * ============================================================================
+ * Stream
+ * ============================================================================
+ */
+
+/// Message Stream.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Component)]
+pub struct MessageStream<T> {
+    /// Id.
+    pub id: Uuid,
+
+    encoding: Encoding,
+
+    end_endpoint: &'static str,
+
+    stream: Stream<chaos_symphony_network::Message>,
+
+    marker: PhantomData<T>,
+}
+
+impl<T> MessageStream<T>
+where
+    T: Decode,
+{
+    /// Creates a new [`MessageStream`].
+    #[must_use]
+    pub fn new(
+        id: Uuid,
+        encoding: Encoding,
+        end_endpoint: &'static str,
+        stream: Stream<chaos_symphony_network::Message>,
+    ) -> Self {
+        Self {
+            id,
+            encoding,
+            end_endpoint,
+            stream,
+            marker: PhantomData,
+        }
+    }
+
+    /// Id.
+    #[must_use]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Try poll.
+    ///
+    /// Ready with each response, then with `None` once the [`StreamEnd`] is
+    /// received.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if bevy-tokio bridge is disconnected, timed out or
+    /// unable to decode response.
+    pub fn try_poll(&self) -> Poll<Result<Option<T>, PollError>> {
+        self.stream.try_poll().map(|result| {
+            result.and_then(|message| {
+                if message.endpoint == self.end_endpoint {
+                    return Ok(None);
+                }
+
+                T::decode(message, self.encoding)
+                    .map(Some)
+                    .map_err(|error| PollError::Decode(error.into()))
+            })
+        })
     }
 }
 
@@ -144,32 +311,51 @@ where
 /// Event.
 pub trait Event<T>
 where
-    Self: Into<chaos_symphony_network::Message>,
+    Self: Encode,
 {
+    /// Channel.
+    const CHANNEL: Channel = Channel::CONTROL;
+
     /// Endpoint.
     const ENDPOINT: &'static str;
 
+    /// Reliability.
+    ///
+    /// Events superseded by later events may prefer
+    /// [`Reliability::Unreliable`].
+    const RELIABILITY: Reliability = Reliability::Reliable;
+
     /// Creates a new [`Message`].
     #[must_use]
     fn message(id: Uuid, payload: T) -> Message<T> {
         Message {
             id,
             endpoint: Self::ENDPOINT.to_string(),
-            header: MessageHeader {
-                source_endpoint_id: None,
-                source_identity: None,
-            },
+            header: MessageHeader::new(),
             payload,
         }
     }
 
+    /// Coalesce key.
+    ///
+    /// Queued events with the same key are superseded by later events when the
+    /// send queue overflows.
+    fn coalesce_key(&self) -> Option<String> {
+        None
+    }
+
     /// Try send.
     ///
     /// # Errors
     ///
-    /// Will return `Err` if bevy-tokio bridge is disconnected.
-    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<(), SendError<NetworkSend>> {
-        endpoint.try_send_non_blocking(self.into())
+    /// Will return `Err` if unable to encode, bevy-tokio bridge is
+    /// disconnected or the send queue is full.
+    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<(), SendError> {
+        let key = self.coalesce_key();
+        let message = self.encode(endpoint.encoding())?;
+        endpoint
+            .try_send_non_blocking_coalesced(Self::CHANNEL, Self::RELIABILITY, key, message)
+            .map_err(SendError::TrySend)
     }
 }
 
@@ -182,22 +368,32 @@ where
 /// Request.
 pub trait Request<T, U>
 where
-    Self: Into<chaos_symphony_network::Message> + MessageId,
-    U: From<chaos_symphony_network::Message>,
+    Self: Encode + MessageId,
+    U: Decode,
 {
     /// Endpoint.
     const ENDPOINT: &'static str;
 
+    /// Notify Cancel.
+    ///
+    /// Whether a [`CancelEvent`] is sent to the peer when the
+    /// [`MessageCallback`] is dropped or times out before ready, so it can
+    /// stop working on the request.
+    const NOTIFY_CANCEL: bool = false;
+
+    /// Timeout.
+    ///
+    /// The [`MessageCallback`] is ready with [`PollError::TimedOut`] when no
+    /// response is received in time.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
     /// Creates a new [`Message`].
     #[must_use]
     fn message(id: Uuid, payload: T) -> Message<T> {
         Message {
             id,
             endpoint: Self::ENDPOINT.to_string(),
-            header: MessageHeader {
-                source_endpoint_id: None,
-                source_identity: None,
-            },
+            header: MessageHeader::new(),
             payload,
         }
     }
@@ -206,18 +402,108 @@ where
     ///
     /// # Errors
     ///
-    /// Will return `Err` if bevy-tokio bridge is disconnected.
-    fn try_send(
-        self,
-        endpoint: &NetworkEndpoint,
-    ) -> Result<MessageCallback<U>, SendError<NetworkSend>> {
+    /// Will return `Err` if unable to encode, bevy-tokio bridge is
+    /// disconnected or the send queue is full.
+    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<MessageCallback<U>, SendError> {
         let id = self.id();
+        let encoding = endpoint.encoding();
+        let cancel = Self::NOTIFY_CANCEL
+            .then(|| cancel(id, encoding))
+            .transpose()?;
         endpoint
-            .try_send_blocking(self.into())
-            .map(|future| MessageCallback::<U>::new(id, future))
+            .try_send_blocking_with_cancel(self.encode(encoding)?, Self::TIMEOUT, cancel)
+            .map(|future| MessageCallback::<U>::new(id, encoding, future))
+            .map_err(SendError::TrySend)
     }
 }
 
+/// Stream Request.
+///
+/// Answered by any number of responses tied to the request id, followed by a
+/// [`StreamEnd`] on [`StreamRequest::END_ENDPOINT`].
+#[allow(clippy::module_name_repetitions)]
+pub trait StreamRequest<T, U>
+where
+    Self: Encode + MessageId,
+    U: Decode,
+{
+    /// Endpoint.
+    const ENDPOINT: &'static str;
+
+    /// End Endpoint.
+    const END_ENDPOINT: &'static str;
+
+    /// Notify Cancel.
+    ///
+    /// Whether a [`CancelEvent`] is sent to the peer when the
+    /// [`MessageStream`] is dropped or times out before the [`StreamEnd`], so
+    /// it can stop streaming.
+    const NOTIFY_CANCEL: bool = false;
+
+    /// Timeout.
+    ///
+    /// The [`MessageStream`] is ready with [`PollError::TimedOut`] when the
+    /// [`StreamEnd`] is not received in time.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Creates a new [`Message`].
+    #[must_use]
+    fn message(id: Uuid, payload: T) -> Message<T> {
+        Message {
+            id,
+            endpoint: Self::ENDPOINT.to_string(),
+            header: MessageHeader::new(),
+            payload,
+        }
+    }
+
+    /// Creates a new [`StreamEnd`] for the request with the id.
+    #[must_use]
+    fn end(id: Uuid) -> StreamEnd {
+        Message {
+            id,
+            endpoint: Self::END_ENDPOINT.to_string(),
+            header: MessageHeader::new(),
+            payload: StreamEndPayload {},
+        }
+    }
+
+    /// Try send.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to encode, bevy-tokio bridge is
+    /// disconnected or the send queue is full.
+    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<MessageStream<U>, SendError> {
+        let id = self.id();
+        let encoding = endpoint.encoding();
+        let cancel = Self::NOTIFY_CANCEL
+            .then(|| cancel(id, encoding))
+            .transpose()?;
+        endpoint
+            .try_send_streaming(
+                self.encode(encoding)?,
+                Self::TIMEOUT,
+                Self::END_ENDPOINT.to_string(),
+                cancel,
+            )
+            .map(|stream| MessageStream::<U>::new(id, encoding, Self::END_ENDPOINT, stream))
+            .map_err(SendError::TrySend)
+    }
+}
+
+/// Creates the [`CancelEvent`] for the request with the id.
+fn cancel(
+    request_id: Uuid,
+    encoding: Encoding,
+) -> Result<chaos_symphony_network::Message, CodecError> {
+    <CancelEvent as Event<CancelEventPayload>>::message(
+        Uuid::new_v4(),
+        CancelEventPayload { request_id },
+    )
+    .encode(encoding)
+}
+
 /*
  * ============================================================================
  * Response
@@ -227,7 +513,7 @@ where
 /// Response.
 pub trait Response<T>
 where
-    Self: Into<chaos_symphony_network::Message>,
+    Self: Encode,
 {
     /// Endpoint.
     const ENDPOINT: &'static str;
@@ -238,10 +524,7 @@ where
         Message {
             id,
             endpoint: Self::ENDPOINT.to_string(),
-            header: MessageHeader {
-                source_endpoint_id: None,
-                source_identity: None,
-            },
+            header: MessageHeader::new(),
             payload,
         }
     }
@@ -250,8 +533,208 @@ where
     ///
     /// # Errors
     ///
-    /// Will return `Err` if bevy-tokio bridge is disconnected.
-    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<(), SendError<NetworkSend>> {
-        endpoint.try_send_non_blocking(self.into())
+    /// Will return `Err` if unable to encode, bevy-tokio bridge is
+    /// disconnected or the send queue is full.
+    fn try_send(self, endpoint: &NetworkEndpoint) -> Result<(), SendError> {
+        let message = self.encode(endpoint.encoding())?;
+        endpoint
+            .try_send_non_blocking(message)
+            .map_err(SendError::TrySend)
+    }
+}
+
+impl StreamEnd {
+    /// Try send.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if unable to encode, bevy-tokio bridge is
+    /// disconnected or the send queue is full.
+    pub fn try_send(self, endpoint: &NetworkEndpoint) -> Result<(), SendError> {
+        let message = self.encode(endpoint.encoding())?;
+        endpoint
+            .try_send_non_blocking(message)
+            .map_err(SendError::TrySend)
+    }
+}
+
+/*
+ * ============================================================================
+ * Error
+ * ============================================================================
+ */
+
+/// Decode Error.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Codec.
+    Codec(CodecError),
+
+    /// Invalid Id.
+    ///
+    /// Message id is not a valid uuid.
+    InvalidId(String),
+}
+
+impl From<CodecError> for DecodeError {
+    fn from(value: CodecError) -> Self {
+        Self::Codec(value)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Codec(_) => write!(f, "unable to decode message"),
+            Self::InvalidId(id) => write!(f, "invalid message id {id:?}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Codec(error) => Some(error),
+            Self::InvalidId(_) => None,
+        }
+    }
+}
+
+/// Send Error.
+#[derive(Debug)]
+pub enum SendError {
+    /// Codec.
+    Codec(CodecError),
+
+    /// Try Send.
+    TrySend(TrySendError),
+}
+
+impl From<CodecError> for SendError {
+    fn from(value: CodecError) -> Self {
+        Self::Codec(value)
+    }
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Codec(_) => write!(f, "unable to encode message"),
+            Self::TrySend(_) => write!(f, "unable to send message"),
+        }
+    }
+}
+
+impl std::error::Error for SendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Codec(error) => Some(error),
+            Self::TrySend(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Uuid;
+    use chaos_symphony_async::{Poll, PollError, Stream};
+    use chaos_symphony_network::Encoding;
+
+    use crate::{
+        AuthenticateRequest, AuthenticateRequestPayload, AuthenticateResponse, Decode as _,
+        DecodeError, Encode as _, Identity, MessageHeader, MessageStream, Request as _,
+    };
+
+    #[test]
+    fn test_encode_decode() {
+        for encoding in [Encoding::Binary, Encoding::Json] {
+            // Arrange
+            let request = AuthenticateRequest::message(
+                Uuid::new_v4(),
+                AuthenticateRequestPayload {
+                    identity: Identity {
+                        id: Uuid::new_v4(),
+                        noun: "client".to_string(),
+                    },
+                    session: Some(Uuid::new_v4()),
+                },
+            );
+
+            // Act
+            let message = request.clone().encode(encoding).unwrap();
+            let decoded = AuthenticateRequest::decode(message, encoding).unwrap();
+
+            // Assert
+            assert_eq!(decoded.id, request.id);
+            assert_eq!(decoded.endpoint, request.endpoint);
+            assert_eq!(decoded.header.sequence, request.header.sequence);
+            assert_eq!(decoded.payload.identity.id, request.payload.identity.id);
+            assert_eq!(decoded.payload.session, request.payload.session);
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_id() {
+        // Arrange
+        let encoding = Encoding::Binary;
+        let mut message = AuthenticateRequest::message(
+            Uuid::new_v4(),
+            AuthenticateRequestPayload {
+                identity: Identity {
+                    id: Uuid::new_v4(),
+                    noun: "client".to_string(),
+                },
+                session: None,
+            },
+        )
+        .encode(encoding)
+        .unwrap();
+        message.id = "invalid".to_string();
+
+        // Act
+        let result = AuthenticateRequest::decode(message, encoding);
+
+        // Assert
+        assert!(matches!(result, Err(DecodeError::InvalidId(id)) if id == "invalid"));
+    }
+
+    #[test]
+    fn test_stream_decode_error() {
+        // Arrange
+        let (sender, receiver) = chaos_symphony_async::channel();
+        let stream = MessageStream::<AuthenticateResponse>::new(
+            Uuid::new_v4(),
+            Encoding::Binary,
+            "/response/test/end",
+            Stream::new(receiver),
+        );
+        let message = |endpoint: &str| chaos_symphony_network::Message {
+            id: "invalid".to_string(),
+            endpoint: endpoint.to_string(),
+            header: Vec::new(),
+            payload: Vec::new(),
+        };
+
+        // Act
+        sender.send(message("/response/test")).unwrap();
+        let invalid = stream.try_poll();
+        sender.send(message("/response/test/end")).unwrap();
+        let end = stream.try_poll();
+
+        // Assert
+        assert!(matches!(invalid, Poll::Ready(Err(PollError::Decode(_)))));
+        assert!(matches!(end, Poll::Ready(Ok(None))));
+    }
+
+    #[test]
+    fn test_sequence_increases() {
+        // Arrange
+        let first = MessageHeader::new();
+
+        // Act
+        let second = MessageHeader::new();
+
+        // Assert
+        assert!(second.sequence > first.sequence);
     }
 }
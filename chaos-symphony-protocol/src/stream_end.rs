@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Message;
+
+/*
+ * ============================================================================
+ * Response
+ * ============================================================================
+ */
+
+/// Stream End.
+///
+/// Sent after the last response to a [`StreamRequest`](crate::StreamRequest),
+/// on its end endpoint.
+#[allow(clippy::module_name_repetitions)]
+pub type StreamEnd = Message<StreamEndPayload>;
+
+/// Stream End Payload.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StreamEndPayload {}
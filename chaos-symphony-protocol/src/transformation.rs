@@ -1,3 +1,4 @@
+use chaos_symphony_network::{Channel, Reliability};
 use serde::{Deserialize, Serialize};
 
 use crate::{Event, Identity, Message, Transformation};
@@ -13,7 +14,15 @@ use crate::{Event, Identity, Message, Transformation};
 pub type TransformationEvent = Message<TransformationEventPayload>;
 
 impl Event<TransformationEventPayload> for TransformationEvent {
+    const CHANNEL: Channel = Channel::REPLICATION;
+
     const ENDPOINT: &'static str = "/event/transformation";
+
+    const RELIABILITY: Reliability = Reliability::Unreliable;
+
+    fn coalesce_key(&self) -> Option<String> {
+        Some(self.payload.entity_identity.to_string())
+    }
 }
 
 /// Transformation Event Payload.
@@ -5,15 +5,15 @@
 
 use std::{str::FromStr as _, sync::mpsc::TryRecvError};
 
-use bevy::{prelude::*, utils::Uuid};
+use bevy::{app::AppExit, prelude::*, utils::Uuid};
 use chaos_symphony_ecs::{
     bevy_config::BevyConfigPlugin,
     types::{Identity, NetworkIdentity, Role},
 };
+use chaos_symphony_network::{default_certificate_directory, CertificateSource, ServerConfig};
 use chaos_symphony_network_bevy::NetworkServer;
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let mut app = App::new();
 
     app.add_plugins(chaos_symphony_ecs::DefaultPlugins {
@@ -22,12 +22,20 @@ async fn main() {
             log_filter: "chaos_symphony_replication".to_string(),
             title: "Chaos Symphony Replication".to_string(),
         },
+        network_client_config: default(),
         network_identity: NetworkIdentity {
             inner: Identity {
                 id: Uuid::from_str("84988f7d-2146-4677-b4f8-6d503f72fea3").unwrap(),
                 noun: "replication".to_string(),
             },
         },
+        network_runtime: default(),
+        network_server_config: ServerConfig::default().with_certificate(
+            CertificateSource::SelfSigned {
+                directory: default_certificate_directory(),
+                subject_alt_names: vec!["localhost".to_string()],
+            },
+        ),
         role: Role::Replication,
     })
     .add_systems(Update, accepted);
@@ -35,11 +43,15 @@ async fn main() {
     app.run();
 }
 
+/// Accepted.
+///
+/// Spawns accepted [`NetworkEndpoint`](chaos_symphony_network_bevy::NetworkEndpoint),
+/// exiting once the server has stopped.
 #[allow(clippy::needless_pass_by_value)]
-fn accepted(mut commands: Commands, server: Res<NetworkServer>) {
+fn accepted(mut commands: Commands, mut exit: EventWriter<AppExit>, server: Res<NetworkServer>) {
     loop {
         match server.try_recv() {
-            Ok(endpoint) => {
+            Ok(Ok(endpoint)) => {
                 let id = endpoint.id();
                 let remote_address = endpoint.remote_address();
 
@@ -50,12 +62,17 @@ fn accepted(mut commands: Commands, server: Res<NetworkServer>) {
                 let _guard = span.enter();
                 info!("connected");
             }
+            Ok(Err(error)) => {
+                error!(error =% error, "server error");
+            }
             Err(TryRecvError::Disconnected) => {
-                panic!("[network:server] disconnected");
+                error!("server stopped, exiting");
+                exit.send(AppExit);
+                return;
             }
             Err(TryRecvError::Empty) => {
                 return;
             }
-        };
+        }
     }
 }
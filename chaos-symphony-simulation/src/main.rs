@@ -20,8 +20,7 @@ use chaos_symphony_ecs::{
 };
 use chaos_symphony_network_bevy::NetworkEndpoint;
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let mut app = App::new();
 
     app.add_plugins(chaos_symphony_ecs::DefaultPlugins {
@@ -30,12 +29,15 @@ async fn main() {
             log_filter: "chaos_symphony_simulation".to_string(),
             title: "Chaos Symphony Simulation".to_string(),
         },
+        network_client_config: default(),
         network_identity: NetworkIdentity {
             inner: Identity {
                 id: Uuid::from_str("d86cb791-fe2f-4f50-85b9-57532d14f037").unwrap(),
                 noun: "simulation".to_string(),
             },
         },
+        network_runtime: default(),
+        network_server_config: default(),
         role: Role::Simulation,
     })
     .add_systems(
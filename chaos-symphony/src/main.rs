@@ -13,8 +13,7 @@ use chaos_symphony_ecs::{
     types::{Identity, NetworkIdentity, Role},
 };
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let mut app = App::new();
 
     app.add_plugins(chaos_symphony_ecs::DefaultPlugins {
@@ -23,12 +22,15 @@ async fn main() {
             log_filter: "chaos_symphony".to_string(),
             title: "Chaos Symphony".to_string(),
         },
+        network_client_config: default(),
         network_identity: NetworkIdentity {
             inner: Identity {
                 id: Uuid::from_str("0d9aa2b8-0860-42c2-aa20-c2e66dac32b4").unwrap(),
                 noun: "client".to_string(),
             },
         },
+        network_runtime: default(),
+        network_server_config: default(),
         role: Role::Client,
     })
     .add_plugins(transformation::TransformationPlugin)